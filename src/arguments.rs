@@ -1,28 +1,120 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
 
 #[derive(Parser, Debug)]
 pub struct Cli {
     #[clap(short, long)]
     pub word: Option<String>,
-    #[clap(short, long)]
+    #[clap(short, long, overrides_with = "no-random")]
     pub random: bool, 
-    #[clap(short = 'D', long)]
+    #[clap(short = 'D', long, global = true, overrides_with = "no-difficult")]
     pub difficult: bool, 
-    #[clap(short = 't', long)]
+    #[clap(short = 't', long, overrides_with = "no-stats")]
     pub stats: bool, 
     #[clap(short = 's', long)]
     pub seed: Option<u64>, 
     #[clap(short = 'd', long)]
     pub day: Option<i32>,
-    #[clap(short = 'f', long = "final-set")]
+    #[clap(short = 'f', long = "final-set", global = true)]
     pub final_set: Option<String>,
-    #[clap(short = 'a', long = "acceptable-set")]
+    #[clap(short = 'a', long = "acceptable-set", global = true)]
     pub acceptable_set: Option<String>,
-    #[clap(short = 'S', long)]
+    #[clap(short = 'S', long, global = true)]
     pub state: Option<String>,
-    #[clap(short, long)]
+    #[clap(short, long, global = true)]
     pub config: Option<String>,
+    #[clap(long = "strict-config", global = true)]
+    pub strict_config: bool,
+    #[clap(short = 'l', long, global = true)]
+    pub length: Option<usize>,
+    #[clap(short = 'p', long, global = true)]
+    pub player: Option<String>,
+    #[clap(short = 'j', long, global = true)]
+    pub jobs: Option<usize>,
 
-    #[clap(long)]
+    #[clap(long, overrides_with = "no-hint")]
     pub hint: bool,
-}
\ No newline at end of file
+    #[clap(long = "hint-count")]
+    pub hint_count: Option<usize>,
+    #[clap(long, overrides_with = "no-evil")]
+    pub evil: bool,
+    #[clap(short = 'b', long)]
+    pub boards: Option<usize>,
+    #[clap(long, overrides_with = "no-tui")]
+    pub tui: bool,
+    #[clap(long, overrides_with = "no-daily")]
+    pub daily: bool,
+    #[clap(long)]
+    pub date: Option<String>,
+    #[clap(long = "shuffle-version")]
+    pub shuffle_version: Option<u32>,
+
+    // turn off a flag that is set in a config file or the environment
+    #[clap(long = "no-random", overrides_with = "random")]
+    pub no_random: bool,
+    #[clap(long = "no-difficult", global = true, overrides_with = "difficult")]
+    pub no_difficult: bool,
+    #[clap(long = "no-stats", overrides_with = "stats")]
+    pub no_stats: bool,
+    #[clap(long = "no-evil", overrides_with = "evil")]
+    pub no_evil: bool,
+    #[clap(long = "no-daily", overrides_with = "daily")]
+    pub no_daily: bool,
+    #[clap(long = "no-hint", overrides_with = "hint")]
+    pub no_hint: bool,
+    #[clap(long = "no-tui", overrides_with = "tui")]
+    pub no_tui: bool,
+
+    #[clap(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Plays every word of the final list with a built-in solver
+    Bench {
+        /// naive, entropy or minimax
+        #[clap(long, default_value = "entropy")]
+        strategy: String,
+    },
+    /// Shows the statistics saved in the state file
+    Stats,
+    /// Ranks the players of the state file by win %, average guesses and max streak
+    Leaderboard,
+    /// Shows or checks the settings
+    Config {
+        #[clap(subcommand)]
+        action: ConfigCommand,
+    },
+    /// Copies the games of a JSON state file into the state given with --state
+    Import {
+        file: String,
+    },
+    /// Writes the games of the state given with --state to a JSON state file
+    Export {
+        file: String,
+    },
+    /// Caches the feedback of every guess against every answer for the solvers and hints
+    Precompute,
+    /// Ranks every accepted word as the first guess
+    Openers {
+        /// entropy, expected (candidates left), worst (largest group left) or length (of whole games)
+        #[clap(long, default_value = "entropy")]
+        metric: String,
+        /// naive, entropy or minimax, the solver that plays the games of the length metric
+        #[clap(long, default_value = "entropy")]
+        strategy: String,
+        /// how many words are printed
+        #[clap(long, default_value = "10")]
+        top: usize,
+        /// the length metric only plays games for this many words with the highest entropy
+        #[clap(long, default_value = "100")]
+        shortlist: usize,
+    },
+}
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommand {
+    /// Prints the effective value of every setting and where it comes from
+    Show,
+    /// Checks the config files for unknown keys, wrong types and missing paths
+    Check,
+}
@@ -0,0 +1,76 @@
+use rayon::prelude::*;
+use wordle::engine::{self, Game, Outcome};
+use wordle::solver::{Solver, Strategy};
+use wordle::WordList;
+use wordle::patterns::PatternMatrix;
+
+// plays every word of the final list with a built-in solver and prints
+// the guess distribution, average guesses, failures and worst-case words
+pub fn run(final_list: &WordList, acceptable_list: &WordList, is_difficult: bool, strategy: Strategy, patterns: Option<&PatternMatrix>, is_tty: bool)
+{
+    let solver = Solver::new(strategy, final_list, acceptable_list, is_difficult, patterns);
+
+    // the first round works out the opening guess that every round starts with,
+    // the other rounds are then played on the thread pool and kept in list order
+    let mut games: Vec<Game> = final_list.iter().take(1).map(|answer| solver.play(answer)).collect();
+    games.par_extend(final_list.par_iter().skip(1).map(|answer| solver.play(answer)));
+
+    // distribution[i] counts the games solved in i+1 guesses
+    let mut distribution: Vec<i32> = vec![0; engine::MAX_GUESSES];
+    let mut failed: Vec<String> = Vec::new();
+    let mut worst: (usize, Vec<String>) = (0, Vec::new());
+
+    for game in games
+    {
+        let count = game.history().len();
+        if game.outcome() == Some(Outcome::CORRECT)
+        {
+            distribution[count - 1] += 1;
+            if count > worst.0
+            {
+                worst = (count, Vec::new());
+            }
+            if count == worst.0
+            {
+                worst.1.push(game.answer().to_string());
+            }
+        }
+        else
+        {
+            failed.push(game.answer().to_string());
+        }
+    }
+
+    let wins: i32 = distribution.iter().sum();
+    let total_guesses: i32 = distribution.iter().enumerate().map(|(i, n)| (i as i32 + 1) * n).sum();
+    let average: f32 = if wins == 0 { 0.0 } else { total_guesses as f32 / wins as f32 };
+
+    // a failed word is always worse than any solved one
+    let worst_words = if failed.is_empty() { &worst.1 } else { &failed };
+
+    if is_tty
+    {
+        let mode = if is_difficult { " (difficult mode)" } else { "" };
+        println!("{}\n", console::style(format!("Benchmark: {}{}", strategy.name(), mode)).bold().cyan());
+        let most = distribution.iter().copied().max().unwrap_or(0).max(1);
+        for (i, n) in distribution.iter().enumerate()
+        {
+            let bar = "#".repeat(((*n as f32 / most as f32) * 40.0).ceil() as usize);
+            println!("{} {} {}", console::style(i + 1).cyan(), console::style(bar).green(), n);
+        }
+        println!("{} {}", console::style("X").cyan(), failed.len());
+        println!("\n{} {}", console::style("Games:").cyan(), final_list.len());
+        println!("{} {:.3}", console::style("Avg. guesses:").cyan(), average);
+        println!("{} {}", console::style("Failures:").cyan(), failed.len());
+        println!("{} {}", console::style("Worst case:").cyan(), worst_words.join(" "));
+    }
+    else
+    {
+        let mode = if is_difficult { "difficult" } else { "normal" };
+        println!("{} {} {}", final_list.len(), strategy.name(), mode);
+        let counts: Vec<String> = distribution.iter().map(|n| n.to_string()).collect();
+        println!("{}", counts.join(" "));
+        println!("{:.3} {}", average, failed.len());
+        println!("{}", worst_words.join(" "));
+    }
+}
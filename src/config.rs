@@ -1,9 +1,23 @@
 use serde::{Serialize, Deserialize};
-use std::fs::File;
-use std::io::{self, Read};
+use serde_json::{Map, Value};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::arguments::Cli;
 
-#[derive(Serialize, Deserialize, Debug)] // to help organize arguments
-pub struct Config 
+// the settings are layered, every layer overrides the ones before it:
+// built-in defaults < global config < project config < environment variables < command line
+
+// config files are JSON, TOML or YAML, told apart by their extension
+// a file without a known extension is read as JSON
+pub const EXTENSIONS: [&str; 4] = ["json", "toml", "yaml", "yml"];
+// the project config of the working directory is wordle.<extension>, -c names another file instead
+pub const PROJECT_CONFIG: &str = "wordle";
+// every setting can also be given as WORDLE_<NAME>, e.g. WORDLE_FINAL_SET
+pub const ENV_PREFIX: &str = "WORDLE_";
+
+#[derive(Serialize, Deserialize, Debug, Default)] // to help organize arguments
+pub struct Config
 {
     random: Option<bool>,
     difficult: Option<bool>,
@@ -14,103 +28,453 @@ pub struct Config
     acceptable_set: Option<String>,
     state: Option<String>,
     word: Option<String>,
+    length: Option<usize>,
+    evil: Option<bool>,
+    daily: Option<bool>,
+    boards: Option<usize>,
+    shuffle_version: Option<u32>,
+    player: Option<String>,
+    hint: Option<bool>,
+    hint_count: Option<usize>,
+    tui: Option<bool>,
+    date: Option<String>,
+    jobs: Option<usize>,
+}
+
+// where the value of a setting came from
+#[derive(Debug, Clone, PartialEq)]
+pub enum Source
+{
+    DEFAULT,
+    GLOBAL(String), // path of the config file
+    PROJECT(String),
+    ENV(String), // name of the variable
+    CLI,
+}
+
+impl Source
+{
+    pub fn name(&self) -> &str
+    {
+        match self
+        {
+            Source::DEFAULT => "default",
+            Source::GLOBAL(_) => "global",
+            Source::PROJECT(_) => "project",
+            Source::ENV(_) => "env",
+            Source::CLI => "cli",
+        }
+    }
+}
+
+impl fmt::Display for Source
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        match self
+        {
+            Source::DEFAULT => write!(f, "built-in default"),
+            Source::GLOBAL(x) => write!(f, "global config {}", x),
+            Source::PROJECT(x) => write!(f, "project config {}", x),
+            Source::ENV(x) => write!(f, "environment variable {}", x),
+            Source::CLI => write!(f, "command line"),
+        }
+    }
 }
 
-fn string_to_option(s: Option<String>) -> Option<String> 
+// something wrong with the settings
+// without --strict-config only a file that cannot be used is reported, it is skipped
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Problem
 {
-    s.filter(|s| !s.is_empty())
+    UNREADABLE(String), // why a config file cannot be used, with the line and column of type errors
+    UNKNOWN_KEY(String, String), // file, key
+    MISSING_PATH(String, String, Source), // what the path is, path, where it was set
 }
 
-// loads information from config file
-pub fn load(filename: &str, args: &mut crate::Arguments)
+impl Problem
 {
-    let mut file = match File::open(filename)
+    pub fn is_unreadable(&self) -> bool
     {
-        Ok(file) => file,
-        Err(err) => 
+        return matches!(self, Problem::UNREADABLE(_));
+    }
+}
+
+impl fmt::Display for Problem
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        match self
         {
-            println!("Failed to open file {}: {}", filename, err);
-            return;
+            Problem::UNREADABLE(x) => write!(f, "{}", x),
+            Problem::UNKNOWN_KEY(file, key) => write!(f, "Unknown key in {}: {}", file, key),
+            Problem::MISSING_PATH(what, path, source) => write!(f, "{} {} does not exist, set in the {}", what, path, source),
         }
+    }
+}
+
+// the effective value of a setting, Null if it is not set
+pub struct Setting
+{
+    pub name: String,
+    pub value: Value,
+    pub source: Source,
+}
+
+// one layer of settings, only the settings it sets
+type Layer = Map<String, Value>;
+
+// the names of all settings
+fn names() -> Vec<String>
+{
+    match serde_json::to_value(Config::default())
+    {
+        Ok(Value::Object(x)) => x.keys().cloned().collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn defaults() -> Layer
+{
+    let mut layer = Layer::new();
+    for name in ["random", "difficult", "stats", "evil", "daily", "hint", "tui"]
+    {
+        layer.insert(name.to_string(), Value::Bool(false));
+    }
+    return layer;
+}
+
+// the first of <base>.json, <base>.toml, <base>.yaml and <base>.yml that exists
+fn find(base: &Path) -> Option<String>
+{
+    return EXTENSIONS.iter()
+        .map(|x| base.with_extension(x))
+        .find(|x| x.is_file())
+        .map(|x| x.to_string_lossy().to_string());
+}
+
+// the config shared by every project, $XDG_CONFIG_HOME/wordle/config.<extension>
+// XDG_CONFIG_HOME defaults to ~/.config and is ignored unless it is an absolute path
+fn global_path() -> Option<String>
+{
+    let config_home = match std::env::var("XDG_CONFIG_HOME")
+    {
+        Ok(x) if Path::new(&x).is_absolute() => PathBuf::from(x),
+        _ => Path::new(&std::env::var("HOME").ok().filter(|x| !x.is_empty())?).join(".config"),
     };
+    return find(&config_home.join("wordle").join("config"));
+}
 
-    // checks that the json file is not empty
-    let mut contents = String::new();
-    if let Err(err) = file.read_to_string(&mut contents)
+fn format_name(filename: &str) -> &'static str
+{
+    match Path::new(filename).extension().and_then(|x| x.to_str())
     {
-        println!("Failed to read file {}: {}", filename, err);
-        return;
+        Some("toml") => "TOML",
+        Some("yaml") | Some("yml") => "YAML",
+        _ => "JSON",
     }
-    if contents.trim() == "{}"
+}
+
+// the line and column of a byte offset, both counted from 1
+fn position(contents: &str, offset: usize) -> (usize, usize)
+{
+    let before = &contents[..offset.min(contents.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.chars().rev().take_while(|c| *c != '\n').count() + 1;
+    return (line, column);
+}
+
+fn toml_error(e: toml::de::Error, contents: &str) -> String
+{
+    let message = e.message().trim().lines().collect::<Vec<&str>>().join(", ");
+    return match e.span()
+    {
+        Some(span) =>
+        {
+            let (line, column) = position(contents, span.start);
+            format!("{} at line {} column {}", message, line, column)
+        }
+        None => message,
+    };
+}
+
+// parses the contents of a config file in the format of its extension
+fn parse(filename: &str, contents: &str) -> Result<Layer, String>
+{
+    let result = match format_name(filename)
+    {
+        "TOML" => toml::from_str(contents).map_err(|e| toml_error(e, contents)),
+        "YAML" => serde_yaml::from_str(contents).map_err(|e| e.to_string()),
+        _ => serde_json::from_str(contents).map_err(|e| e.to_string()),
+    };
+    // an empty YAML file is null
+    return match result
+    {
+        Ok(Value::Object(x)) => Ok(x),
+        Ok(Value::Null) => Ok(Layer::new()),
+        Ok(_) => Err("the settings must be a table of keys".to_string()),
+        Err(e) => Err(e),
+    };
+}
+
+// reading the file straight into Config tells where a value of the wrong type is
+fn type_error(filename: &str, contents: &str) -> Option<String>
+{
+    return match format_name(filename)
+    {
+        "TOML" => toml::from_str::<Config>(contents).err().map(|e| toml_error(e, contents)),
+        "YAML" => serde_yaml::from_str::<Config>(contents).err().map(|e| e.to_string()),
+        _ => serde_json::from_str::<Config>(contents).err().map(|e| e.to_string()),
+    };
+}
+
+// reads a config file and returns the problems found in it
+// a file that cannot be used gives no settings, empty strings count as not set
+fn read_file(filename: &str) -> (Option<Layer>, Vec<Problem>)
+{
+    let contents = match fs::read_to_string(filename)
+    {
+        Ok(x) => x,
+        Err(err) => return (None, vec![Problem::UNREADABLE(format!("Failed to open file {}: {}", filename, err))]),
+    };
+    let layer: Layer = match parse(filename, &contents)
+    {
+        Ok(x) => x,
+        Err(err) => return (None, vec![Problem::UNREADABLE(format!("Failed to parse {}: {}", filename, err))]),
+    };
+    let known = names();
+    let mut problems: Vec<Problem> = layer.keys()
+        .filter(|x| !known.contains(x))
+        .map(|x| Problem::UNKNOWN_KEY(filename.to_string(), x.clone()))
+        .collect();
+    let layer: Layer = layer.into_iter().filter(|(_, v)| !v.is_null() && v.as_str() != Some("")).collect();
+    // the types are checked before anything of the file is used
+    if let Err(err) = serde_json::from_value::<Config>(Value::Object(layer.clone()))
     {
-        return;
+        let err = type_error(filename, &contents).unwrap_or(err.to_string());
+        problems.push(Problem::UNREADABLE(format!("Failed to parse {}: {}", filename, err)));
+        return (None, problems);
     }
+    return (Some(layer), problems);
+}
 
-    let temp: std::result::Result<Config, _> = serde_json::from_str(&contents);
+// a value given as text is taken as a string if the setting is one,
+// otherwise as a boolean or a number
+fn parse_text(name: &str, text: &str) -> Option<Value>
+{
+    let fits = |value: &Value|
+    {
+        let mut layer = Layer::new();
+        layer.insert(name.to_string(), value.clone());
+        serde_json::from_value::<Config>(Value::Object(layer)).is_ok()
+    };
+    let candidates = [
+        Value::String(text.to_string()),
+        match text.to_lowercase().as_str()
+        {
+            "1" | "true" | "yes" | "on" => Value::Bool(true),
+            "0" | "false" | "no" | "off" => Value::Bool(false),
+            _ => Value::Null,
+        },
+        serde_json::from_str(text).unwrap_or(Value::Null),
+    ];
+    return candidates.into_iter().find(|x| !x.is_null() && fits(x));
+}
 
-    // makes sure that info from config file does not overwrite information
-    match temp 
+fn environment() -> Result<Vec<(Source, Layer)>, String>
+{
+    let mut layers = Vec::new();
+    for name in names()
     {
-        Ok(config) => 
+        let variable = format!("{}{}", ENV_PREFIX, name.to_uppercase());
+        if let Ok(text) = std::env::var(&variable)
         {
-            if let Some(word) = config.word 
-            {
-                if args.word == None
-                {
-                    args.word = string_to_option(Some(word));
-                }
-            }
-            if let Some(difficult) = config.difficult 
-            {
-                args.difficult = difficult || args.difficult;
-            }
-            if let Some(random) = config.random 
-            {
-                args.random = random || args.random;
-            }
-            if let Some(stats) = config.stats 
-            {
-                args.stats = stats || args.stats;
-            }
-            if let Some(day) = config.day 
-            {
-                if args.day == None
-                {
-                    args.day = Some(day);
-                }
-            }
-            if let Some(seed) = config.seed 
+            if text.is_empty()
             {
-                if args.seed == None
-                {
-                    args.seed = Some(seed);
-                }
+                continue;
             }
-            if let Some(final_set) = config.final_set 
+            let value = match parse_text(&name, &text)
             {
-                if args.final_set == None
-                {
-                    args.final_set = string_to_option(Some(final_set));
-                }
-            }
-            if let Some(acceptable_set) = config.acceptable_set 
+                Some(x) => x,
+                None => return Err(format!("Invalid value for {}: {}", variable, text)),
+            };
+            let mut layer = Layer::new();
+            layer.insert(name, value);
+            layers.push((Source::ENV(variable), layer));
+        }
+    }
+    return Ok(layers);
+}
+
+// a flag given as --x or --no-x, the last one wins
+fn flag(on: bool, off: bool) -> Option<bool>
+{
+    if on { return Some(true); }
+    if off { return Some(false); }
+    return None;
+}
+
+fn command_line(cli: &Cli) -> Layer
+{
+    let config = Config
+    {
+        random: flag(cli.random, cli.no_random),
+        difficult: flag(cli.difficult, cli.no_difficult),
+        stats: flag(cli.stats, cli.no_stats),
+        day: cli.day,
+        seed: cli.seed,
+        final_set: cli.final_set.clone(),
+        acceptable_set: cli.acceptable_set.clone(),
+        state: cli.state.clone(),
+        word: cli.word.clone(),
+        length: cli.length,
+        evil: flag(cli.evil, cli.no_evil),
+        daily: flag(cli.daily, cli.no_daily),
+        boards: cli.boards,
+        shuffle_version: cli.shuffle_version,
+        player: cli.player.clone(),
+        hint: flag(cli.hint, cli.no_hint),
+        hint_count: cli.hint_count,
+        tui: flag(cli.tui, cli.no_tui),
+        date: cli.date.clone(),
+        jobs: cli.jobs,
+    };
+    match serde_json::to_value(config)
+    {
+        Ok(Value::Object(x)) => x.into_iter().filter(|(_, v)| !v.is_null()).collect(),
+        _ => Layer::new(),
+    }
+}
+
+// collects every layer from the lowest to the highest precedence
+// the config files are checked on the way, files that cannot be used are left out
+fn layers(cli: &Cli, problems: &mut Vec<Problem>) -> Result<Vec<(Source, Layer)>, String>
+{
+    let mut layers = vec![(Source::DEFAULT, defaults())];
+    let project = match &cli.config
+    {
+        Some(x) => Some(x.clone()),
+        None => find(Path::new(PROJECT_CONFIG)),
+    };
+    let files = [global_path().map(Source::GLOBAL), project.map(Source::PROJECT)];
+    for source in files.into_iter().flatten()
+    {
+        let filename = match &source
+        {
+            Source::GLOBAL(x) | Source::PROJECT(x) => x.clone(),
+            _ => continue,
+        };
+        let (layer, mut found) = read_file(&filename);
+        problems.append(&mut found);
+        if let Some(layer) = layer
+        {
+            layers.push((source, layer));
+        }
+    }
+    layers.append(&mut environment()?);
+    layers.push((Source::CLI, command_line(cli)));
+    return Ok(layers);
+}
+
+// the word lists must exist, and so must the directory a state file is written to
+fn missing_paths(settings: &Vec<Setting>) -> Vec<Problem>
+{
+    let mut problems = Vec::new();
+    for setting in settings
+    {
+        let (what, path) = match (setting.name.as_str(), setting.value.as_str())
+        {
+            ("final_set", Some(x)) | ("acceptable_set", Some(x)) => (setting.name.clone(), PathBuf::from(x)),
+            ("state", Some(x)) =>
             {
-                if args.acceptable_set == None
+                let file = Path::new(x.strip_prefix(crate::progress::SQLITE_PREFIX).unwrap_or(x));
+                match file.parent().filter(|p| !p.as_os_str().is_empty())
                 {
-                    args.acceptable_set = string_to_option(Some(acceptable_set));
+                    Some(dir) => ("state directory".to_string(), dir.to_path_buf()),
+                    None => continue,
                 }
             }
-            if let Some(state) = config.state 
+            _ => continue,
+        };
+        if !path.exists()
+        {
+            problems.push(Problem::MISSING_PATH(what, path.to_string_lossy().to_string(), setting.source.clone()));
+        }
+    }
+    return problems;
+}
+
+// resolves the settings of all layers into args
+// returns where each one came from and the problems found on the way
+pub fn load(cli: &Cli, args: &mut crate::Arguments) -> Result<(Vec<Setting>, Vec<Problem>), String>
+{
+    let mut problems: Vec<Problem> = Vec::new();
+    let mut settings: Vec<Setting> = names().into_iter()
+        .map(|name| Setting { name, value: Value::Null, source: Source::DEFAULT })
+        .collect();
+    for (source, layer) in layers(cli, &mut problems)?
+    {
+        for setting in settings.iter_mut()
+        {
+            if let Some(value) = layer.get(&setting.name)
             {
-                if args.state == None
-                {
-                    args.state = string_to_option(Some(state));
-                }
+                setting.value = value.clone();
+                setting.source = source.clone();
             }
         }
-        Err(err) => 
+    }
+
+    let merged: Layer = settings.iter().map(|x| (x.name.clone(), x.value.clone())).collect();
+    let config: Config = match serde_json::from_value(Value::Object(merged))
+    {
+        Ok(x) => x,
+        Err(err) => return Err(format!("Invalid settings: {}", err)),
+    };
+    args.word = config.word;
+    args.random = config.random.unwrap_or(false);
+    args.difficult = config.difficult.unwrap_or(false);
+    args.stats = config.stats.unwrap_or(false);
+    args.seed = config.seed;
+    args.day = config.day;
+    args.final_set = config.final_set;
+    args.acceptable_set = config.acceptable_set;
+    args.state = config.state;
+    args.length = config.length;
+    args.evil = config.evil.unwrap_or(false);
+    args.boards = config.boards;
+    args.daily = config.daily.unwrap_or(false);
+    args.shuffle_version = config.shuffle_version;
+    args.player = config.player;
+    args.hint = config.hint.unwrap_or(false);
+    args.hint_count = config.hint_count;
+    args.tui = config.tui.unwrap_or(false);
+    args.date = config.date;
+    args.jobs = config.jobs;
+    problems.append(&mut missing_paths(&settings));
+    return Ok((settings, problems));
+}
+
+// prints the effective settings and their sources
+// the test friendly version has one line per setting: <name> <value or -> <source>
+pub fn show(settings: &Vec<Setting>, is_tty: bool)
+{
+    for setting in settings
+    {
+        let value = match &setting.value
         {
-            println!("Failed to parse JSON: {}", err);
+            Value::Null => "-".to_string(),
+            Value::String(x) => x.clone(),
+            x => x.to_string(),
+        };
+        if is_tty
+        {
+            println!("{} = {} {}", console::style(&setting.name).cyan(), console::style(value).bold(),
+                console::style(format!("({})", setting.source)).dim());
+        }
+        else
+        {
+            println!("{} {} {}", setting.name, value, setting.source.name());
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,531 @@
+use std::collections::HashMap;
+use std::fmt;
+use crate::wordlist::WordList;
+
+// the engine holds the rules of wordle without doing any input or output,
+// frontends read guesses, pass them to Game::guess() and print the Feedback
+
+// default number of guesses a player gets in one round
+pub const MAX_GUESSES: usize = 6;
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, PartialEq, Clone)]
+pub enum GuessError // used to represent why a guess was not accepted
+{
+    WRONG_LENGTH(usize), // the number of letters a guess needs
+    NOT_ALPHABETIC,
+    NOT_IN_LIST,
+    MISSING_GREEN(usize, char), // difficult mode: position (from 0) and the letter it must keep
+    MISSING_YELLOW(char), // difficult mode: a letter the guess must contain
+    FINISHED,
+}
+
+// friendly messages for the user, the test-friendly output prints INVALID instead
+impl fmt::Display for GuessError
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        match self
+        {
+            GuessError::WRONG_LENGTH(n) => write!(f, "Guess must have {} letters", n),
+            GuessError::NOT_ALPHABETIC => write!(f, "Guess must only contain letters"),
+            GuessError::NOT_IN_LIST => write!(f, "Not in word list"),
+            GuessError::MISSING_GREEN(i, c) => write!(f, "{} letter must be {}", ordinal(i + 1), c),
+            GuessError::MISSING_YELLOW(c) => write!(f, "Guess must contain {}", c),
+            GuessError::FINISHED => write!(f, "The round is already over"),
+        }
+    }
+}
+
+// 1st, 2nd, 3rd, 4th, ..., 11th, 12th, 13th, ..., 21st
+fn ordinal(n: usize) -> String
+{
+    let suffix = match (n % 10, n % 100)
+    {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    return format!("{}{}", n, suffix);
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Outcome // used to represent the possible outcomes of a round
+{
+    CORRECT,
+    FAILED
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Status // used to represent the four colors a character can have
+{
+    G, Y, R, X
+}
+
+// result of a single accepted guess
+#[derive(Clone, Debug, PartialEq)]
+pub struct Feedback
+{
+    pub word: String,
+    pub statuses: Vec<Status>,
+}
+
+// checks if the input satisfies requirements: word_length alphable characters, uppercase
+pub fn valid_input(word: &String, is_final: bool, final_list: &WordList, acceptable_list: &WordList, word_length: usize) -> bool
+{
+    return check_input(word, is_final, final_list, acceptable_list, word_length).is_ok();
+}
+
+// like valid_input, but tells why the input was rejected
+pub fn check_input(word: &String, is_final: bool, final_list: &WordList, acceptable_list: &WordList, word_length: usize) -> Result<(), GuessError>
+{
+
+    if word.chars().count() != word_length {return Err(GuessError::WRONG_LENGTH(word_length));}
+    for i in word.chars()
+    {
+        if !i.is_ascii_uppercase() {return Err(GuessError::NOT_ALPHABETIC);}
+    }
+    if final_list.contains(word)
+    {
+        return Ok(());
+    }
+
+    // the following if statement is used to differenciate between final values
+    // and acceptable values
+
+    if !is_final && acceptable_list.contains(word) // is_final = false then also accept values from acceptable
+    {
+        return Ok(());
+    }
+    return Err(GuessError::NOT_IN_LIST);
+}
+
+// check if a guess is correct by seeing if all its characters are Status::G
+pub fn check_correct(guess: &[Status]) -> bool
+{
+    for i in guess
+    {
+        if *i != Status::G {return false;}
+    }
+    return true;
+}
+
+// the compare function compares the guess and the answer and calculates
+// the status of the guess and alphabet
+
+pub fn compare(answer: &String, guess: String) -> (Vec<Status>, [Status; 26])
+{
+    let mut result = vec![Status::X ; guess.chars().count()];
+    let mut letter_status = [Status::X ; 26];
+
+    // first, all elements in the guess status are set to red
+    for (i, c) in guess.chars().enumerate()
+    {
+        result[i] = Status::R;
+        letter_status[(c as usize) - ('A' as usize)] = Status::R;
+    }
+    // then, all the correct characters are set to green
+    for (i, (a, g)) in answer.chars().zip(guess.chars()).enumerate()
+    {
+        if a == g
+        {
+            result[i] = Status::G;
+            letter_status[(g as usize) - ('A' as usize)] = Status::G;
+        }
+    }
+    // then, an array keeps track of how freequently each character appears
+    // this is used later to see which values should be yellow and which
+    // should be red (those characters that already have a green status are ignored
+    // because the net change in count is 0)
+
+    let mut letter_count = [0i32; 26];
+    for (i, ch) in answer.chars().enumerate()
+    {
+        if result[i] != Status::G && ch.is_ascii_uppercase()
+        {
+            letter_count[(ch as usize) - ('A' as usize)] += 1;
+        }
+    }
+
+    // finally, characters are assigned as yellow depending on how many more/less
+    // times that character appears in the guess compared to the answer
+    for (i, (_a, g)) in answer.chars().zip(guess.chars()).enumerate()
+    {
+        if result[i] != Status::G
+        {
+            let x = &mut letter_count[(g as usize) - ('A' as usize)];
+            if *x > 0
+            {
+                result[i] = Status::Y;
+                if letter_status[(g as usize) - ('A' as usize)] != Status::G
+                {
+                    letter_status[(g as usize) - ('A' as usize)] = Status::Y;
+                }
+                *x -= 1;
+            }
+        }
+    }
+
+    return (result, letter_status);
+}
+
+// function used to update information that each new guess provides to the alphabet
+// it adds new information on top of the old information using priority: G>Y>R>X
+pub fn merge(mut s1: [Status; 26], s2: [Status; 26]) -> [Status; 26]
+{
+    for i in 0..26
+    {
+        if s1[i] == Status::G || s2[i] == Status::G {s1[i] = Status::G ; continue;}
+        else if s1[i] == Status::Y || s2[i] == Status::Y {s1[i] = Status::Y ; continue;}
+        else if s1[i] == Status::R || s2[i] == Status::R {s1[i] = Status::R ; continue;}
+        else {continue;}
+    }
+    return s1
+}
+
+// keeps track of which characters have already been confirmed as green
+// used in difficult mode
+pub fn update_known_greens(mut greens: Vec<Status>, p1: &[Status]) -> Vec<Status>
+{
+    for i in 0..greens.len()
+    {
+        if p1[i] == Status::G {greens[i] = Status::G;}
+    }
+    return greens;
+}
+
+// additional check for input values in the difficult mode
+pub fn check_valid_difficult(guess: &String, answer: &String, greens: &[Status], alphabet: [Status; 26]) -> bool
+{
+    return check_difficult(guess, answer, greens, alphabet).is_ok();
+}
+
+// like check_valid_difficult, but tells which rule the guess breaks first
+pub fn check_difficult(guess: &String, answer: &String, greens: &[Status], alphabet: [Status; 26]) -> Result<(), GuessError>
+{
+    for i in 0..greens.len() // checks that all green characters are fixed
+    {
+        if greens[i] == Status::G && guess.chars().nth(i) != answer.chars().nth(i)
+        {
+            return Err(GuessError::MISSING_GREEN(i, answer.chars().nth(i).unwrap_or(' ')));
+        }
+    }
+    for i in 0..26 // checks that all yellow charecters appear
+    {
+        let c = (('A' as usize) + i) as u8 as char;
+        if alphabet[i] == Status::Y && !guess.contains(c)
+        {
+            return Err(GuessError::MISSING_YELLOW(c));
+        }
+    }
+    return Ok(());
+}
+
+// state of a single round: the answer, the rules in use and every accepted guess
+pub struct Game<'a>
+{
+    answer: String,
+    final_list: &'a WordList<'a>,
+    acceptable_list: &'a WordList<'a>,
+    is_difficult: bool,
+    max_guesses: usize,
+    alphabet: [Status; 26],
+    greens: Vec<Status>,
+    history: Vec<Feedback>,
+    candidates: Option<Vec<&'a str>>, // only used in evil mode
+}
+
+impl<'a> Game<'a>
+{
+    // starts a new round, the word length is taken from the answer
+    pub fn new(answer: &str, final_list: &'a WordList<'a>, acceptable_list: &'a WordList<'a>, is_difficult: bool) -> Game<'a>
+    {
+        let answer = answer.to_uppercase();
+        Game
+        {
+            greens: vec![Status::X ; answer.chars().count()],
+            answer,
+            final_list,
+            acceptable_list,
+            is_difficult,
+            max_guesses: MAX_GUESSES,
+            alphabet: [Status::X ; 26],
+            history: Vec::new(),
+            candidates: None,
+        }
+    }
+
+    // starts an evil round: no answer is chosen up front, instead every guess
+    // keeps the largest group of candidates that are still possible
+    // there is no guess limit, the round ends once the player pins down the answer
+    pub fn new_evil(candidates: &'a [&'a str], final_list: &'a WordList<'a>, acceptable_list: &'a WordList<'a>, is_difficult: bool) -> Game<'a>
+    {
+        let mut candidates: Vec<&'a str> = candidates.to_vec();
+        candidates.sort();
+        let mut game = Game::new(candidates.first().copied().unwrap_or(""), final_list, acceptable_list, is_difficult);
+        game.max_guesses = usize::MAX;
+        game.candidates = Some(candidates);
+        return game;
+    }
+
+    // EVIL MODE START
+    // splits the candidates by the statuses `word` would get against them and keeps
+    // the largest group, preferring groups that reveal fewer greens and yellows
+    // the answer is then moved to a word of that group
+    fn narrow_candidates(&mut self, word: &String)
+    {
+        let candidates = match &self.candidates
+        {
+            Some(x) => x,
+            None => return,
+        };
+
+        let mut buckets: HashMap<Vec<Status>, Vec<&'a str>> = HashMap::new();
+        for e in candidates
+        {
+            let (status, _) = compare(&e.to_uppercase(), word.clone());
+            buckets.entry(status).or_insert_with(Vec::new).push(e);
+        }
+
+        let mut buckets: Vec<(Vec<Status>, Vec<&'a str>)> = buckets.into_iter().collect();
+        buckets.sort_by_key(|(status, words)|
+        {
+            let greens = status.iter().filter(|s| **s == Status::G).count();
+            let yellows = status.iter().filter(|s| **s == Status::Y).count();
+            (std::cmp::Reverse(words.len()), greens, yellows, words[0])
+        });
+
+        if let Some((_, words)) = buckets.into_iter().next()
+        {
+            self.answer = words[0].to_uppercase();
+            self.candidates = Some(words);
+        }
+    }
+    // EVIL MODE END
+
+    pub fn is_evil(&self) -> bool
+    {
+        return self.candidates.is_some();
+    }
+
+    pub fn is_difficult(&self) -> bool
+    {
+        return self.is_difficult;
+    }
+
+    // number of answers still possible in evil mode
+    pub fn remaining(&self) -> Option<usize>
+    {
+        return self.candidates.as_ref().map(|x| x.len());
+    }
+
+    // validates a guess and, if it is accepted, records it and returns its result
+    // a rejected guess does not use up one of the player's tries
+    pub fn guess(&mut self, word: &str) -> Result<Feedback, GuessError>
+    {
+        if self.is_over()
+        {
+            return Err(GuessError::FINISHED);
+        }
+
+        let word = word.trim().to_uppercase();
+        self.check(&word)?;
+
+        if self.is_evil()
+        {
+            self.narrow_candidates(&word);
+        }
+        let (statuses, letters) = compare(&self.answer, word.clone());
+        self.alphabet = merge(self.alphabet, letters);
+        self.greens = update_known_greens(self.greens.clone(), &statuses);
+
+        let feedback = Feedback { word, statuses };
+        self.history.push(feedback.clone());
+        return Ok(feedback);
+    }
+
+    // checks if an uppercase word would be accepted as the next guess
+    pub fn is_allowed(&self, word: &String) -> bool
+    {
+        return self.check(word).is_ok();
+    }
+
+    // like is_allowed, but tells why a word would be rejected
+    pub fn check(&self, word: &String) -> Result<(), GuessError>
+    {
+        check_input(word, false, self.final_list, self.acceptable_list, self.word_length())?;
+        // DIFFICULT MODE START
+        if self.is_difficult
+        {
+            check_difficult(word, &self.answer, &self.greens, self.alphabet)?;
+        }
+        // DIFFICULT MODE END
+        return Ok(());
+    }
+
+    // checks the difficult mode rules only, for words already known to be in the lists
+    pub fn meets_constraints(&self, word: &String) -> bool
+    {
+        // DIFFICULT MODE START
+        if self.is_difficult && !check_valid_difficult(word, &self.answer, &self.greens, self.alphabet)
+        {
+            return false;
+        }
+        // DIFFICULT MODE END
+        return true;
+    }
+
+    // a round is over once the answer is found or all tries are used
+    pub fn is_over(&self) -> bool
+    {
+        return self.is_won() || self.history.len() >= self.max_guesses;
+    }
+
+    pub fn is_won(&self) -> bool
+    {
+        match self.history.last()
+        {
+            Some(feedback) => check_correct(&feedback.statuses),
+            None => false,
+        }
+    }
+
+    // None while the round is still being played
+    pub fn outcome(&self) -> Option<Outcome>
+    {
+        if self.is_won() { Some(Outcome::CORRECT) }
+        else if self.is_over() { Some(Outcome::FAILED) }
+        else { None }
+    }
+
+    // merged status of every letter of the alphabet so far
+    pub fn alphabet(&self) -> [Status; 26]
+    {
+        return self.alphabet;
+    }
+
+    pub fn history(&self) -> &[Feedback]
+    {
+        return &self.history;
+    }
+
+    // the guessed words in the order they were made
+    pub fn guesses(&self) -> Vec<String>
+    {
+        return self.history.iter().map(|f| f.word.clone()).collect();
+    }
+
+    // in evil mode the answer can still change until the round is over
+    pub fn answer(&self) -> &str
+    {
+        return &self.answer;
+    }
+
+    pub fn word_length(&self) -> usize
+    {
+        return self.greens.len();
+    }
+
+    pub fn max_guesses(&self) -> usize
+    {
+        return self.max_guesses;
+    }
+
+    pub fn set_max_guesses(&mut self, max_guesses: usize)
+    {
+        self.max_guesses = max_guesses;
+    }
+}
+
+// several boards played at once (dordle, quordle, ...): every guess goes to
+// each board that is not solved yet, and the player gets one extra guess per board
+pub struct MultiGame<'a>
+{
+    boards: Vec<Game<'a>>,
+    guesses: Vec<String>,
+}
+
+impl<'a> MultiGame<'a>
+{
+    // one board per answer with a shared budget of (number of boards + 5) guesses
+    pub fn new(answers: &[String], final_list: &'a WordList<'a>, acceptable_list: &'a WordList<'a>, is_difficult: bool) -> MultiGame<'a>
+    {
+        let max_guesses = MAX_GUESSES + answers.len().max(1) - 1;
+        let mut boards: Vec<Game<'a>> = Vec::new();
+        for answer in answers
+        {
+            let mut board = Game::new(answer, final_list, acceptable_list, is_difficult);
+            board.set_max_guesses(max_guesses);
+            boards.push(board);
+        }
+        MultiGame { boards, guesses: Vec::new() }
+    }
+
+    // wraps a single prepared game, for example an evil one
+    pub fn from_game(game: Game<'a>) -> MultiGame<'a>
+    {
+        MultiGame { boards: vec![game], guesses: Vec::new() }
+    }
+
+    // applies a guess to every unsolved board, solved boards get None
+    // the guess has to be accepted by all unsolved boards
+    pub fn guess(&mut self, word: &str) -> Result<Vec<Option<Feedback>>, GuessError>
+    {
+        if self.is_over()
+        {
+            return Err(GuessError::FINISHED);
+        }
+
+        let word = word.trim().to_uppercase();
+        for board in self.boards.iter().filter(|b| !b.is_over())
+        {
+            board.check(&word)?;
+        }
+
+        let mut feedbacks: Vec<Option<Feedback>> = Vec::new();
+        for board in self.boards.iter_mut()
+        {
+            if board.is_over()
+            {
+                feedbacks.push(None);
+            }
+            else
+            {
+                feedbacks.push(Some(board.guess(&word)?));
+            }
+        }
+        self.guesses.push(word);
+        return Ok(feedbacks);
+    }
+
+    // over once every board is solved or the shared guesses are used up
+    pub fn is_over(&self) -> bool
+    {
+        return self.boards.iter().all(|b| b.is_over());
+    }
+
+    // the game is only won if every board was solved
+    pub fn outcome(&self) -> Option<Outcome>
+    {
+        if !self.is_over() { None }
+        else if self.boards.iter().all(|b| b.is_won()) { Some(Outcome::CORRECT) }
+        else { Some(Outcome::FAILED) }
+    }
+
+    pub fn boards(&self) -> &[Game<'a>]
+    {
+        return &self.boards;
+    }
+
+    pub fn guesses(&self) -> &[String]
+    {
+        return &self.guesses;
+    }
+
+    pub fn answers(&self) -> Vec<String>
+    {
+        return self.boards.iter().map(|b| b.answer().to_string()).collect();
+    }
+}
@@ -0,0 +1,103 @@
+// packed feedback codes
+//
+// the statuses of a guess with up to 5 letters fit in one base 3 number:
+// the status of letter i is digit i of the code (R = 0, Y = 1, G = 2),
+// so 5 letters give 3^5 = 243 codes, small enough for a u8 or an array index
+// codes are computed on the bytes of the words with fixed-size letter counts,
+// which lets solvers score millions of pairs without allocating
+
+use crate::engine::Status;
+
+pub type Code = u8;
+
+// the longest word a code can describe
+pub const MAX_LETTERS: usize = 5;
+// number of distinct codes, every code is below this
+pub const CODES: usize = 243;
+
+// feedback for `guess` against `answer`, both ASCII words of the same length
+// and at most MAX_LETTERS letters, in any case
+// gives the same statuses as engine::compare
+// panics on words of different lengths or longer than MAX_LETTERS
+pub fn code(answer: &str, guess: &str) -> Code
+{
+    let answer = answer.as_bytes();
+    let guess = guess.as_bytes();
+    assert!(guess.len() <= MAX_LETTERS && answer.len() == guess.len(),
+        "feedback codes need words of the same length with at most {} letters, got {:?} and {:?}",
+        MAX_LETTERS, String::from_utf8_lossy(answer), String::from_utf8_lossy(guess));
+
+    // greens first, the other letters of the answer are counted for the yellows
+    let mut digits = [0u8; MAX_LETTERS];
+    let mut counts = [0u8; 26];
+    for i in 0..guess.len()
+    {
+        let a = answer[i].to_ascii_uppercase();
+        if a == guess[i].to_ascii_uppercase()
+        {
+            digits[i] = 2;
+        }
+        else
+        {
+            counts[(a - b'A') as usize] += 1;
+        }
+    }
+    // a letter is yellow while the answer still has an unmatched copy of it
+    for i in 0..guess.len()
+    {
+        let g = (guess[i].to_ascii_uppercase() - b'A') as usize;
+        if digits[i] == 0 && counts[g] > 0
+        {
+            digits[i] = 1;
+            counts[g] -= 1;
+        }
+    }
+
+    let mut code: Code = 0;
+    for i in (0..guess.len()).rev()
+    {
+        code = code * 3 + digits[i];
+    }
+    return code;
+}
+
+// scores one guess against every word of a list, codes[i] belongs to answers[i]
+pub fn codes(guess: &str, answers: &[&str]) -> Vec<Code>
+{
+    return answers.iter().map(|answer| code(answer, guess)).collect();
+}
+
+// packs the statuses of a word with at most MAX_LETTERS letters, X counts as R
+pub fn encode(statuses: &[Status]) -> Code
+{
+    let mut code: Code = 0;
+    for status in statuses.iter().rev()
+    {
+        let digit = match status
+        {
+            Status::G => 2,
+            Status::Y => 1,
+            Status::R | Status::X => 0,
+        };
+        code = code * 3 + digit;
+    }
+    return code;
+}
+
+// unpacks a code, positions past the end of a shorter word come out as R
+pub fn decode(code: Code) -> [Status; MAX_LETTERS]
+{
+    let mut statuses = [Status::R; MAX_LETTERS];
+    let mut rest = code;
+    for status in statuses.iter_mut()
+    {
+        *status = match rest % 3
+        {
+            2 => Status::G,
+            1 => Status::Y,
+            _ => Status::R,
+        };
+        rest /= 3;
+    }
+    return statuses;
+}
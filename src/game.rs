@@ -1,176 +1,223 @@
 use std::collections::HashMap;
 use std::io::{self, Write};
 use crate::progress;
-
-#[derive(Debug)]
-pub enum Error // used to represent errors: in this case, only INVALID is needed
+use crate::stats;
+use wordle::engine::{self, Feedback, Game, GuessError, MultiGame, Outcome, Status};
+use wordle::solver::{self, Suggestion};
+use wordle::WordList;
+use wordle::patterns::PatternMatrix;
+
+// the settings of a run, resolved once in main and shared by both frontends
+pub struct Settings<'a>
 {
-    INVALID,
+    pub is_tty: bool,
+    pub final_list: &'a WordList<'a>,
+    pub acceptable_list: &'a WordList<'a>,
+    pub patterns: Option<&'a PatternMatrix>,
+    pub word: Option<String>, // the answer given with -w
+    pub difficult: bool,
+    pub stats: bool,
+    pub random: bool,
+    pub seed: u64,
+    pub day: i32, // the day of the first random round
+    pub state: Option<String>, // the progress is saved there if it is given
+    pub player: Option<String>,
+    pub hint: bool,
+    pub hint_count: usize,
+    pub word_length: usize,
+    pub evil: bool,
+    pub boards: usize,
+    pub date: Option<String>, // the date of a daily puzzle
 }
 
-#[derive(Debug, PartialEq)]
-pub enum Outcome // used to represent the possible outcomes of a round
+// the answers and rules of a round, set up the same way by both frontends
+pub struct Round
 {
-    CORRECT,
-    FAILED
+    pub answers: Vec<String>, // empty if the player gives them or evil mode picks one while playing
+    pub replay: Vec<String>, // the guesses of a resumed round, played first
+    pub started: String,
+    pub mode: progress::Mode,
 }
 
-// checks if the input satisfies requirements: 5 alphable characters, uppercase
-fn valid_input(word: &String, is_final: bool, final_list: &Vec<&str>, acceptable_list: &Vec<&str>) -> bool
+impl Round
 {
-    
-    if word.len() != 5 {return false;}
-    for i in word.chars()
+    // true if the player has to give the answers of the round
+    pub fn needs_answers(&self) -> bool
     {
-        if !i.is_uppercase() {return false;}
+        return self.answers.is_empty() && !self.mode.evil;
     }
-    for element in final_list
+
+    // saves the round as unfinished after an accepted guess, the errors name the state file
+    pub fn save(&self, settings: &Settings, answers: Vec<String>, guesses: Vec<String>) -> Result<(), String>
     {
-        if element.to_string().to_uppercase() == *word
+        let filename = match &settings.state
         {
-            return true;
-        }
+            Some(x) => x,
+            None => return Ok(()),
+        };
+        return progress::save_round(filename, settings.player.as_deref(), answers, guesses, &self.started, &self.mode)
+            .map_err(|e| format!("Failed to save {}: {}", filename, e));
     }
 
-    // the following if statement is used to differenciate between final values
-    // and acceptable values
-
-    if !is_final // is_final = false then also accept values from acceptable
+    // adds the finished round to the history, `total_rounds` is updated to the saved count
+    pub fn finish(self, settings: &Settings, guesses: &Vec<String>, answers: Vec<String>, outcome: Outcome,
+        total_rounds: &mut i32) -> Result<(), String>
     {
-        for element in acceptable_list
+        let filename = match &settings.state
         {
-            if element.to_string().to_uppercase() == *word
-            {
-                return true;
-            }
-        }
+            Some(x) => x,
+            None => return Ok(()),
+        };
+        return progress::update(filename, settings.player.as_deref(), guesses, total_rounds, answers, outcome, self.started, self.mode)
+            .map_err(|e| format!("Failed to save {}: {}", filename, e));
     }
-    return false;
 }
 
-// gets the input from the user
-fn get_input(is_final: bool, final_list: &Vec<&str>, acceptable_list: &Vec<&str>) -> Result<String, Error>
+// reads the history of the player and the round they left unfinished, if it can be
+// played with words of the current length
+pub fn load_history(settings: &Settings, results: &mut Vec<(Outcome, i32)>, word_count: &mut HashMap<String, i32>,
+    total_rounds: &mut i32) -> Option<progress::Unfinished>
 {
-    let mut word = String::new();
-    let _ = io::stdin().read_line(&mut word);
-    let word = word.trim().to_string().to_uppercase();
-    
-    // If the input value does not satisfy the format or is not in its intended list,
-    // then the function will return Error::INVALID to signal asking for input again
-
-    match valid_input(&word, is_final, final_list, acceptable_list)
+    let filename = settings.state.as_deref()?;
+    if let Err(e) = progress::load(filename, settings.player.as_deref(), results, word_count, total_rounds)
     {
-        true => Ok(word),
-        false => Err(Error::INVALID)
+        eprintln!("Failed to load {}: {}", filename, e);
     }
+    return progress::resumable(filename, settings.player.as_deref(), settings.word_length);
 }
 
-#[derive(Clone, Copy, PartialEq, Debug)]
-pub enum Status // used to represent the four colors a character can have
+// records an unfinished round the player does not want to resume as a loss
+// returns true if it was the daily puzzle being played, which it uses up
+pub fn give_up(settings: &Settings, unfinished: progress::Unfinished, results: &mut Vec<(Outcome, i32)>,
+    word_count: &mut HashMap<String, i32>, total_rounds: &mut i32) -> Result<bool, String>
 {
-    G, Y, R, X
+    let is_daily = settings.date.is_some() && unfinished.mode.date == settings.date;
+    if let Some(filename) = &settings.state
+    {
+        progress::give_up(filename, settings.player.as_deref(), unfinished, results, word_count, total_rounds)
+            .map_err(|e| format!("Failed to save {}: {}", filename, e))?;
+    }
+    return Ok(is_daily);
 }
 
-// check if a guess is correct by seeing if all its 5 characters are Status::G
-fn check_correct(guess: [Status; 5]) -> bool
+// sets up the next round: the `resume`d one if there is one, otherwise a new one with the answers
+// of -w or of `day` in random mode, `day` moves on by the boards used
+// None once the random rounds have run past the end of the final list
+pub fn next_round(settings: &Settings, resume: Option<progress::Unfinished>, day: &mut i32) -> Option<Round>
 {
-    for i in guess
+    let mut round = Round
     {
-        if i != Status::G {return false;}
+        answers: Vec::new(),
+        replay: Vec::new(),
+        started: progress::timestamp(),
+        mode: progress::Mode
+        {
+            difficult: settings.difficult,
+            hint: settings.hint,
+            evil: settings.evil,
+            seed: None,
+            day: None,
+            length: Some(settings.word_length),
+            date: settings.date.clone(),
+        },
+    };
+    // a resumed round keeps its guesses, start time and the rules it was started with
+    if let Some(x) = resume
+    {
+        round.answers = x.answers;
+        round.replay = x.guesses;
+        round.started = x.started.unwrap_or(round.started);
+        round.mode = progress::Mode { hint: x.mode.hint || settings.hint, ..x.mode };
+    }
+    else if settings.evil
+    {
+        // evil mode picks its answer while playing
+    }
+    else if settings.random
+    {
+        // main checks the first round, later rounds can run past the end of the list
+        let words = &settings.final_list[((*day - 1) as usize).min(settings.final_list.len())..];
+        if words.len() < settings.boards
+        {
+            return None;
+        }
+        round.answers = words[..settings.boards].iter().map(|w| w.to_string()).collect();
+        round.mode.seed = Some(settings.seed);
+        round.mode.day = Some(*day);
+        *day += settings.boards as i32; // for each round played, day count increases by the boards used
     }
-    return true;
+    else if let Some(x) = &settings.word
+    {
+        round.answers = vec![x.clone()];
+    }
+    return Some(round);
 }
 
-// prints status of the most recent guess
-fn print_arr_5(arr: [Status; 5])
+// reads one line from the user, None once the input has ended
+fn read_line() -> Option<String>
 {
-    for x in arr {print!("{:?}", x);}
-}
-// prints status of the entire alphabet
-fn print_arr_26(arr: [Status; 26])
-{
-    for x in arr {print!("{:?}", x);}
+    let mut input = String::new();
+    match io::stdin().read_line(&mut input)
+    {
+        Ok(0) | Err(_) => None,
+        Ok(_) => Some(input),
+    }
 }
 
-// the compare function compares the guess and the answer and calculates
-// the status of the guess and alphabet
-
-fn compare(answer: &String, guess: String) -> ([Status; 5], [Status; 26]) 
+// gets the input from the user, None once the input has ended
+fn get_input(is_final: bool, final_list: &WordList, acceptable_list: &WordList, word_length: usize) -> Option<Result<String, GuessError>>
 {
-    let mut result = [Status::X ; 5];
-    let mut letter_status = [Status::X ; 26];
+    let word = read_line()?;
+    let word = word.trim().to_string().to_uppercase();
+    
+    // If the input value does not satisfy the format or is not in its intended list,
+    // then the function will return the reason as GuessError to signal asking for input again
 
-    // first, all elements in the guess status are set to red
-    for (i, c) in guess.chars().enumerate()
+    match engine::check_input(&word, is_final, final_list, acceptable_list, word_length)
     {
-        result[i] = Status::R;
-        letter_status[(c as usize) - ('A' as usize)] = Status::R;
+        Ok(()) => Some(Ok(word)),
+        Err(e) => Some(Err(e))
     }
-    // then, all the correct characters are set to green
-    for (i, (a, g)) in answer.chars().zip(guess.chars()).enumerate() 
+}
+
+// problems with the state file are reported, but do not stop the game
+fn report_save(result: Result<(), String>)
+{
+    if let Err(e) = result
     {
-        if a == g
-        {
-            result[i] = Status::G;
-            letter_status[(g as usize) - ('A' as usize)] = Status::G;
-        }
+        eprintln!("{}", e);
     }
-    // then, I use a HashMap to keep track of how freequently each character appears
-    // this is used later to see which values should be yellow and which
-    // should be red (those characters that already have a green status are ignored
-    // because the net change in count is 0)
+}
 
-    let mut letter_count: HashMap<char, i32> = HashMap::new();
-    for (i, ch) in answer.chars().enumerate() 
+// tells the user why the input was rejected
+// the test-friendly output keeps printing INVALID for every reason
+fn print_error(e: &GuessError, is_tty: bool)
+{
+    if is_tty
     {
-        let x = letter_count.entry(ch).or_insert(0);
-        if result[i] != Status::G
-        {
-            *x += 1;
-        }
+        println!("{}", console::style(e).red());
     }
-
-    // finally, characters are assigned as yellow depending on how many more/less
-    // times that character appears in the guess compared to the answer
-    for (i, (_a, g)) in answer.chars().zip(guess.chars()).enumerate() 
+    else
     {
-        if result[i] != Status::G
-        {
-            if let Some(x) = letter_count.get_mut(&g) {
-                if *x > 0
-                {
-                    result[i] = Status::Y;
-                    if letter_status[(g as usize) - ('A' as usize)] != Status::G
-                    {
-                        letter_status[(g as usize) - ('A' as usize)] = Status::Y;
-                    }
-                    *x -= 1;
-                }
-            }
-        }
+        println!("INVALID");
     }
-    
-    return (result, letter_status);
 }
 
-// function used to update information that each new guess provides to the alphabet
-// it adds new information on top of the old information using priority: G>Y>R>X
-fn merge(mut s1: [Status; 26], s2: [Status; 26]) -> [Status; 26]
+// prints status of the most recent guess (one status per letter)
+fn print_arr_word(arr: &[Status])
 {
-    for i in 0..26
-    {
-        if s1[i] == Status::G || s2[i] == Status::G {s1[i] = Status::G ; continue;}
-        else if s1[i] == Status::Y || s2[i] == Status::Y {s1[i] = Status::Y ; continue;}
-        else if s1[i] == Status::R || s2[i] == Status::R {s1[i] = Status::R ; continue;}
-        else {continue;}
-    }
-    return s1
+    for x in arr {print!("{:?}", x);}
+}
+// prints status of the entire alphabet
+fn print_arr_26(arr: [Status; 26])
+{
+    for x in arr {print!("{:?}", x);}
 }
 
 // prints test-friendly result
-fn print_no_tty(a: [Status; 5], b:[Status; 26])
+fn print_no_tty(a: &[Status], b:[Status; 26])
 {
-    print_arr_5(a);
+    print_arr_word(a);
     print!(" ");
     print_arr_26(b);
     print!("\n");
@@ -197,7 +244,7 @@ fn get_display(c: char, status: Status) -> console::StyledObject<char>
 }
 
 // prints user-friendly result
-fn print_tty(a: [Status; 5], b:[Status; 26], word: String)
+fn print_tty(a: &[Status], b:[Status; 26], word: String)
 {
     for (i, e) in word.chars().enumerate() 
     {
@@ -213,219 +260,162 @@ fn print_tty(a: [Status; 5], b:[Status; 26], word: String)
     print!("\n\n");
 }
 
-// keeps track of which characters have already been confirmed as green
-// used in difficult mode
-fn update_known_greens(mut greens: [Status; 5], p1: [Status; 5]) -> [Status; 5]
+// prints the test-friendly result of every board that received the guess,
+// each line starts with the number of the board
+fn print_boards_no_tty(feedbacks: &Vec<Option<Feedback>>, boards: &[Game])
 {
-    for i in 0..5
+    for (i, (feedback, board)) in feedbacks.iter().zip(boards).enumerate()
     {
-        if p1[i] == Status::G {greens[i] = Status::G;}
+        if let Some(f) = feedback
+        {
+            print!("{} ", i + 1);
+            print_no_tty(&f.statuses, board.alphabet());
+        }
     }
-    return greens;
 }
-// additional check for input values in the difficult mode
-fn check_valid_difficult(guess: &String, answer: &String, greens: [Status; 5], alphabet: [Status; 26]) -> bool
+
+// prints the user-friendly boards side by side, boards solved earlier stay blank
+// and the alphabet of every board that is still being played is shown below
+fn print_boards_tty(feedbacks: &Vec<Option<Feedback>>, boards: &[Game])
 {
-    for i in 0..5 // checks that all green characters are fixed
+    for (feedback, board) in feedbacks.iter().zip(boards)
     {
-        if greens[i] == Status::G && guess.chars().nth(i) != answer.chars().nth(i)
+        match feedback
         {
-            return false;
+            Some(f) =>
+            {
+                for (i, e) in f.word.chars().enumerate()
+                {
+                    print!("{}", get_display(e, f.statuses[i]));
+                }
+            }
+            None => print!("{}", " ".repeat(board.word_length())),
         }
+        print!("   ");
     }
-    for i in 0..26 // checks that all yellow charecters appear
+    print!("\n");
+    for (i, board) in boards.iter().enumerate()
     {
-        if alphabet[i] == Status::Y && !guess.contains((('A' as usize) + i) as u8 as char)
+        if board.is_won() { continue; }
+        print!("{} ", console::style(i + 1).cyan());
+        let alphabet = board.alphabet();
+        for j in 0..26
         {
-            return false
+            print!("{}", get_display((('A' as u8) + j) as char, alphabet[j as usize]));
         }
+        print!("\n");
     }
-    return true;
-}
-
-fn check_valid_hint(status: [Status; 5], e: &str, word: String) -> bool
-{
-    let (e_status, _) = compare(&e.to_string(), word);
-    if status == e_status
-    {
-        return true;
-    }
-    return false
+    print!("\n");
 }
 
-
-// // check if a word is a possible solution
-// fn check_valid_hint(status: [Status; 5], e: &str, word: String) -> bool
-// {
-//     for (i, c) in word.chars().enumerate()
-//     {
-//         // ensures that the green characters are matching between word and correct answer
-//         if status[i] == Status::G 
-//         {
-//             if c != e.chars().nth(i).unwrap()
-//             {
-                
-//                 return false;
-//             }
-//         }
-//         // ensures that yellow character are included in the word
-//         if status[i] == Status::Y
-//         {
-//             if !e.contains(c)
-//             {
-//                 return false;
-//             }
-//             // ensures that a yellow character is not in that same position again
-//             if c == e.chars().nth(i).unwrap()
-//             {
-//                 return false;
-//             }
-//         }
-//         // ensures that a red character is not in that same position again
-//         if status[i] == Status::R
-//         {
-//             if c == e.chars().nth(i).unwrap()
-//             {
-//                 return false;
-//             }
-//         }
-//     }
-
-//     // frequency of character in solution >= yellow + green
-//     let mut letter_count: HashMap<char, i32> = HashMap::new(); 
-//     for (i, ch) in word.clone().chars().enumerate() 
-//     {
-//         if status[i] == Status::Y || status[i] == Status::G
-//         {
-//             let x = letter_count.entry(ch).or_insert(0);
-//             *x += 1;
-//         }
-//     }
-//     // frequency of each character character in current string
-//     let mut letter_count_2: HashMap<char, i32> = HashMap::new();
-//     for (i, ch) in e.clone().chars().enumerate() 
-//     {
-//         let x = letter_count_2.entry(ch).or_insert(0);
-//         *x += 1;
-//     }
-//     for (i, c) in word.clone().chars().enumerate()
-//     {
-//         if status[i] == Status::Y || status[i] == Status::G
-//         {
-//             // less appearences in current string than in the solution
-//             if *letter_count.get_mut(&c).unwrap() > *letter_count_2.get_mut(&c).unwrap()
-//             {
-//                 return false;
-//             }
-//         }
-//     }
-    
-//     return true;
-// }
-
-// game function starts a new wordle round
-fn round(is_tty: bool, answer: &String, final_list: &Vec<&str>, acceptable_list: &Vec<&str>, is_difficult: bool,
-     word_count: &mut HashMap<String, i32>, is_hint: bool) 
--> Result<(Outcome, i32, Vec<String>), Error> // Result<(correct/failed, #of tries)>
+// game function starts a new wordle round on one or more boards
+// the guesses of a resumed round are played first
+// every accepted guess is saved to the state file until the round is over
+// returns None if the input ended before the round was finished
+fn round(settings: &Settings, mut game: MultiGame, word_count: &mut HashMap<String, i32>, next: &Round) 
+-> Option<(Outcome, i32, Vec<String>, Vec<String>)> // Option<(correct/failed, #of tries, guesses, answers)>
 {
     
     // storing information about the current round
-    let mut count: i32 = 0;
-    let mut alphabet = [Status::X ; 26];
-    let mut greens = [Status::X ; 5];
-    let mut guesses: Vec<String> = Vec::new();
     let mut possible: Vec<&str> = Vec::new();
 
-    if is_hint // used for hint mode
+    if settings.hint // used for hint mode, every answer word is possible at first
     {
-        possible = acceptable_list.clone();
+        possible = settings.final_list.to_vec();
     }
 
-    while count < 6 // count to keep track of how many guesses used
+    let mut replay = next.replay.iter().cloned();
+    while !game.is_over()
     {
-        count += 1;
-        let word: String;
-        loop // loop used to get a valid input from user
+        let input = match replay.next()
         {
-            if is_tty // all "if is_tty" are used for user friendly output 
+            Some(x) => x,
+            None =>
             {
-                println!("{}", console::style("Enter a guess: ").blue());
-            }
-             
-            let guess: Result<String, Error> = get_input(false, &final_list, &acceptable_list);
-
-            // if the input is not valid, then the user is asked for input again
-            match guess 
-            {
-                Ok(x) => {
-                    // DIFFICULT MODE START
-                    if is_difficult && !check_valid_difficult(&x, &answer.clone(), greens, alphabet)
-                    {
-                        println!("{:?}", Error::INVALID);
-                        continue;
-                    }
-                    // DIFFICULT MODE END
-
-                    word = x;
-                    break; 
+                if settings.is_tty // all "if settings.is_tty" are used for user friendly output 
+                {
+                    println!("{}", console::style("Enter a guess: ").blue());
                 }
-                Err(e) =>  {println!("{:?}", e);}
+                read_line()?
             }
-        }
-
-        // if the user guess is valid, then calculations begin now:
+        };
 
-        guesses.push(word.clone()); // keeps track of all guesses
-
-        // obtains results for printing
-        let (p1, p2) = compare(&answer.clone(), word.clone());
+        // if the input is not valid, then the user is asked for input again
+        let feedbacks = match game.guess(&input)
+        {
+            Ok(feedbacks) => feedbacks,
+            Err(e) => {print_error(&e, settings.is_tty); continue;}
+        };
+        let word = input.trim().to_uppercase();
 
-        // updates alphabet information
-        alphabet = merge(alphabet, p2);
+        // SAVE UNFINISHED ROUND
+        report_save(next.save(settings, game.answers(), game.guesses().to_vec()));
+        // END SAVE UNFINISHED ROUND
 
-        if is_tty
+        // a single board keeps the original output
+        if game.boards().len() > 1
         {
-            print_tty(p1, alphabet, word.clone());
+            if settings.is_tty { print_boards_tty(&feedbacks, game.boards()); }
+            else { print_boards_no_tty(&feedbacks, game.boards()); }
         }
-        else {
-            print_no_tty(p1, alphabet);
-        }
-
-        // finds all words that are still possible solutions based on new result
-        if is_hint
+        else if let Some(Some(feedback)) = feedbacks.first()
         {
-            print!("Hint: possible words: \n");
-            let mut temp: Vec<&str> = Vec::new();
-            for e in &possible
+            let board = &game.boards()[0];
+            if settings.is_tty
             {
-                if check_valid_hint(p1, &e.to_uppercase(), word.clone())
-                {
-                    temp.push(e);
-                }
+                print_tty(&feedback.statuses, board.alphabet(), word.clone());
+            }
+            else {
+                print_no_tty(&feedback.statuses, board.alphabet());
             }
-            possible = temp;
-            print!("{:?} \n", possible);
-        }
 
-        // DIFFICULT MODE START
-        if is_difficult
-        {
-            greens = update_known_greens(greens, p1);
+            // finds all words that are still possible solutions based on new result
+            // and suggests the guesses that are expected to narrow them down the most
+            if settings.hint && !board.is_over()
+            {
+                possible = solver::filter_candidates(&possible, &feedback.statuses, &word);
+                let pool: Vec<&str> = settings.final_list.pairs().chain(settings.acceptable_list.pairs())
+                    .filter(|(_, upper)| board.meets_constraints(upper)).map(|(w, _)| w).collect();
+                let suggestions = solver::rank_guesses(&pool, &possible, settings.hint_count, settings.patterns);
+                print_hint(&suggestions, possible.len(), settings.is_tty);
+            }
         }
-        // DIFFICULT MODE END
 
         // STATS MODE START
         let x = word_count.entry(word.clone()).or_insert(0);
         *x += 1;
         // STATS MODE END
+    }
+
+    let count = game.guesses().len() as i32;
+    let outcome = game.outcome()?;
+    return Some((outcome, count, game.guesses().to_vec(), game.answers()));
+}
 
-        if check_correct(p1) 
-        { 
-            return Ok((Outcome::CORRECT, count, guesses));
+// prints the ranked suggestions of hint mode
+fn print_hint(suggestions: &Vec<Suggestion>, remaining: usize, is_tty: bool)
+{
+    if is_tty
+    {
+        println!("{} {} {}", console::style("Hint:").bold().cyan(),
+            console::style(remaining).cyan(), console::style("possible words left").cyan());
+        for (i, s) in suggestions.iter().enumerate()
+        {
+            let mark = if s.is_candidate { "(possible answer)" } else { "" };
+            println!("{:>2}. {} {} {}", i + 1, console::style(&s.word).bold(),
+                console::style(format!("{:.2} bits", s.score)).cyan(), console::style(mark).green());
+        }
+        print!("\n");
+    }
+    else
+    {
+        println!("Hint: {} possible words", remaining);
+        for s in suggestions
+        {
+            if s.is_candidate { println!("{} {:.3} *", s.word, s.score); }
+            else { println!("{} {:.3}", s.word, s.score); }
         }
     }
-    
-    return Ok((Outcome::FAILED, 6, guesses))
 }
 
 // asks user whether to continue to the next round
@@ -449,10 +439,9 @@ fn ask_continue() -> bool {
     }
 }
 
-// calculates prints the statistics in the mode --stats
-fn print_stats(results: &Vec<(Outcome, i32)>, word_count: &HashMap<String, i32>, is_tty: bool)
-{   
-    // calculates wins, losses, and average guesses in wins
+// calculates wins, losses, and average guesses in wins
+pub fn summary(results: &Vec<(Outcome, i32)>) -> (i32, i32, f32)
+{
     let mut x = 0;
     let mut y = 0;
     let mut total_attempts: f32 = 0.0;
@@ -471,6 +460,13 @@ fn print_stats(results: &Vec<(Outcome, i32)>, word_count: &HashMap<String, i32>,
     let z: f32;
     if x == 0 { z = 0.0;}
     else { z = total_attempts/(x as f32); }
+    return (x, y, z);
+}
+
+// calculates prints the statistics in the mode --stats
+fn print_stats(results: &Vec<(Outcome, i32)>, word_count: &HashMap<String, i32>, is_tty: bool)
+{   
+    let (x, y, z) = summary(results);
 
     // for user friendly version
     if is_tty
@@ -479,6 +475,7 @@ fn print_stats(results: &Vec<(Outcome, i32)>, word_count: &HashMap<String, i32>,
         println!("{} {}", console::style("Wins:").cyan(), console::style(x).cyan());
         println!("{} {}", console::style("Losses:").cyan(), console::style(y).cyan());
         println!("{} {}", console::style("Avg. tries:").cyan(), console::style(z).cyan());
+        stats::print_tty(results);
     }
     else {
         print!("{} {} {:.2}\n", x, y, z);
@@ -519,11 +516,10 @@ fn print_stats(results: &Vec<(Outcome, i32)>, word_count: &HashMap<String, i32>,
 
 
 // function starts the actual game
-pub fn start(is_tty: bool, final_list: &Vec<&str>, acceptable_list: &Vec<&str>, mut word_arg: Option<String>,
-     is_difficult: bool, show_stats: bool, is_random: bool, mut day: i32, record_progress: bool, progress_file: String, is_hint: bool)
+pub fn start(settings: &Settings)
 {
     
-    if is_tty
+    if settings.is_tty
     {
         println!("{}", console::style("Welcome to WORDLE!").bold().blink().blue());
     }
@@ -535,117 +531,159 @@ pub fn start(is_tty: bool, final_list: &Vec<&str>, acceptable_list: &Vec<&str>,
 
     // READ PROGRESS FILE
 
-    if record_progress
+    let mut resume = load_history(settings, &mut results, &mut word_count, &mut total_rounds);
+
+    // END READ PROGRESS FILE
+
+    // RESUME UNFINISHED ROUND
+
+    if let Some(x) = resume.take()
     {
-        // print!("RUNNING LOAD()\n");
-        progress::load(&progress_file, &mut results, &mut word_count, &mut total_rounds);
+        if settings.is_tty
+        {
+            println!("{} {} {}", console::style("An unfinished round with").blue(),
+                console::style(x.guesses.len()).bold().blue(), console::style("guess/es was found").blue());
+            println!("{}", console::style("Type 'Y' to resume it and 'N' to give it up\n").blue());
+        }
+        if ask_continue()
+        {
+            resume = Some(x);
+        }
+        else
+        {
+            match give_up(settings, x, &mut results, &mut word_count, &mut total_rounds)
+            {
+                Ok(false) => {}
+                Ok(true) =>
+                {
+                    if settings.is_tty
+                    {
+                        println!("{}", console::style("Come back tomorrow for the next puzzle!\n").blue());
+                    }
+                    return;
+                }
+                Err(e) =>
+                {
+                    // the round is still saved as unfinished, a new one would replace it
+                    eprintln!("{}", e);
+                    return;
+                }
+            }
+        }
     }
 
-    // END READ PROGRESS FILE
+    // END RESUME UNFINISHED ROUND
 
+    // the day of the next random round
+    let mut day = settings.day;
     loop // loop to ask if to play again
     {
-
-        loop // loop to check for valid inut
-        { 
-            let mut word: Result<String, Error> = Err(Error::INVALID);
-            if !is_random
+        let mut next = match next_round(settings, resume.take(), &mut day)
+        {
+            Some(x) => x,
+            None =>
             {
-                if let Some(ref mut x) = word_arg
+                if settings.is_tty
                 {
-                    word = Ok(x.to_string());
+                    println!("{}", console::style("There are no more words to play, thank you for playing!\n").blue());
                 }
-                else 
+                return;
+            }
+        };
+        // one answer is needed for every board
+        let is_asked = next.needs_answers();
+        while is_asked && next.answers.len() < settings.boards
+        {
+            if settings.is_tty
+            {
+                println!("{}", console::style("Enter the solution: ").blue());
+            }
+            match get_input(true, settings.final_list, settings.acceptable_list, settings.word_length)
+            {
+                Some(Ok(x)) => next.answers.push(x),
+                Some(Err(e)) => {print_error(&e, settings.is_tty);}
+                None => return, // the input has ended
+            }
+        }
+
+        let game = if next.mode.evil
+        {
+            MultiGame::from_game(Game::new_evil(settings.final_list, settings.final_list, settings.acceptable_list, next.mode.difficult))
+        }
+        else
+        {
+            MultiGame::new(&next.answers, settings.final_list, settings.acceptable_list, next.mode.difficult)
+        };
+        match round(settings, game, &mut word_count, &next)
+        {
+            Some((outcome, count, guesses, x)) => 
+            {
+                // round is finished, printing different types of outcomes
+                if outcome == Outcome::CORRECT
                 {
-                    if is_tty
+                    if settings.is_tty
                     {
-                        println!("{}", console::style("Enter the solution: ").blue());
+                        println!("{} {} {}", 
+                        console::style("You won in").blue(), 
+                        console::style(count).bold().blink().blue(), 
+                        console::style("tries").blue());
+                    }
+                    else {
+                        println!("{:?} {}", outcome, count);
                     }
-                    word = get_input(true, &final_list, &acceptable_list);
                 }
-            }
-            else
-            {
-                for (i, &element) in final_list.iter().enumerate() {
-                    if (i == (day-1) as usize)
+                else
+                {
+                    if settings.is_tty
                     {
-                        word = Ok(element.clone().to_string());
-                        break;
+                        println!("{} {}", 
+                        console::style("You lost. Correct answer: ").blue(), 
+                        console::style(x.join(" ")).bold().blink().blue()
+                        )
+                    }
+                    else {
+                        println!("{:?} {}", outcome, x.join(" "));
                     }
                 }
-                day += 1; // for each round played, day count increases
+
+                results.push((outcome, count));
+                total_rounds += 1;
+
+                // WRITE TO JSON PROGRESS FILE
+
+                report_save(next.finish(settings, &guesses, x, outcome, &mut total_rounds));
+
+                // END WRITE TO JSON PROGRESS FILE
             }
-            
-            let result: Result<(Outcome, i32, Vec<String>), Error>;
-            // if the indicated word is not valid, then the used is asked for input again
-            match word
+            None =>
             {
-                Ok(mut x) => 
+                // the input has ended in the middle of the round
+                if settings.is_tty && settings.state.is_some()
                 {
-                    x = x.to_uppercase();
-                    result = round(is_tty, &x, final_list, acceptable_list, is_difficult, &mut word_count, is_hint);
-                    match result
-                    {
-                        Ok((outcome, count, guesses)) => 
-                        {
-                            // round is finished, printing different types of outcomes
-                            if outcome == Outcome::CORRECT
-                            {
-                                if is_tty
-                                {
-                                    println!("{} {} {}", 
-                                    console::style("You won in").blue(), 
-                                    console::style(count).bold().blink().blue(), 
-                                    console::style("tries").blue());
-                                }
-                                else {
-                                    println!("{:?} {}", outcome, count);
-                                }
-                            }
-                            else
-                            {
-                                if is_tty
-                                {
-                                    println!("{} {}", 
-                                    console::style("You lost. Correct answer: ").blue(), 
-                                    console::style(count).bold().blink().blue()
-                                    )
-                                }
-                                else {
-                                    println!("{:?} {}", outcome, x);
-                                }
-                            }
-
-                            results.push((outcome, count));
-                            total_rounds += 1;
-
-                            // WRITE TO JSON PROGRESS FILE
-
-                            if record_progress
-                            {
-                                progress::update(&progress_file, &guesses , &mut total_rounds , x);
-                            }
-
-                            // END WRITE TO JSON PROGRESS FILE
-
-                            break;
-                        }
-                        Err(e) => {println!("{:?}", e);}
-                    }
+                    println!("{}", console::style("The round was saved, use the same state file to resume it").blue());
                 }
-                Err(e) => {println!("{:?}", e);}
+                return;
             }
         }
 
         // STATS MODE START
-        if show_stats
+        if settings.stats
         {
-            print_stats(&results, &word_count, is_tty);
+            print_stats(&results, &word_count, settings.is_tty);
         }
         // STATS MODE END
 
+        // there is only one daily puzzle per date
+        if settings.date.is_some()
+        {
+            if settings.is_tty
+            {
+                println!("{}", console::style("Come back tomorrow for the next puzzle!\n").blue());
+            }
+            return;
+        }
 
-        if is_tty
+        if settings.is_tty
         {
             println!("{}", console::style("Type 'Y' if you wish to continue and 'N' if you wish to quit\n").blue());
         }
@@ -653,11 +691,11 @@ pub fn start(is_tty: bool, final_list: &Vec<&str>, acceptable_list: &Vec<&str>,
         // asks if the user wants to continue plauing
         if !ask_continue() 
         {
-            if is_tty
+            if settings.is_tty
             {
                 println!("{}", console::style("Thank you for playing!\n").blue());
             }
             return;
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,12 @@
+// library part of wordle: the built-in word lists and the game engine,
+// which does no input or output so it can be embedded and tested directly
+pub mod builtin_words;
+pub mod engine;
+pub mod feedback;
+pub mod patterns;
+pub mod solver;
+pub mod shuffle;
+pub mod wordlist;
+
+pub use engine::{Feedback, Game, GuessError, MultiGame, Outcome, Status};
+pub use wordlist::WordList;
@@ -1,16 +1,27 @@
 mod game;
-mod builtin_words;
 mod arguments;
 mod readfilemode;
 mod progress;
 mod config;
+mod bench;
+mod tui;
+mod stats;
+mod sqlite;
+mod openers;
 use clap::Parser;
-use rand::rngs::StdRng;
-use rand::prelude::*;
+use wordle::builtin_words;
+use wordle::solver;
+use wordle::WordList;
+use wordle::patterns::{self, PatternMatrix};
+use wordle::shuffle::{self, Scheme, Shuffler};
 use std::io::prelude::*;
 use std::fs::File;
+use chrono::{Local, NaiveDate};
 
-#[derive(Debug)]
+// the date of the first daily puzzle, every later date moves one word further
+const DAILY_EPOCH: (i32, u32, u32) = (2021, 6, 19);
+
+#[derive(Debug, Default)]
 struct Arguments // help organize arguments
 {
     word: Option<String>,
@@ -22,40 +33,156 @@ struct Arguments // help organize arguments
     final_set: Option<String>,
     acceptable_set: Option<String>,
     state: Option<String>,
+    length: Option<usize>,
+    evil: bool,
+    boards: Option<usize>,
+    daily: bool,
+    shuffle_version: Option<u32>,
+    player: Option<String>,
+    hint: bool,
+    hint_count: Option<usize>,
+    tui: bool,
+    date: Option<String>,
+    jobs: Option<usize>,
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> 
 {    
     let cli = arguments::Cli::parse();
 
-    let mut args = Arguments
-    {
-        word : cli.word, 
-        random: cli.random,
-        difficult: cli.difficult, 
-        stats: cli.stats,
-        seed: cli.seed,
-        day: cli.day,
-        final_set: cli.final_set,
-        acceptable_set: cli.acceptable_set,
-        state: cli.state,
-    };
+    let mut args = Arguments::default();
 
     // HANDLE CONFIG
 
-    // If config file is provided then it will load the arguments that have not been 
-    // provided earlier
+    // the arguments are resolved from the built-in defaults, the global and project
+    // config files, the environment and the command line, each overriding the one before
+
+    let (settings, problems) = config::load(&cli, &mut args)?;
+
+    // with --strict-config every problem is an error, otherwise a config file
+    // that cannot be used is skipped and everything else is ignored
+    let is_check = matches!(cli.command, Some(arguments::Command::Config { action: arguments::ConfigCommand::Check }));
+    if cli.strict_config || is_check
+    {
+        if !problems.is_empty()
+        {
+            for x in &problems
+            {
+                if is_check { println!("{}", x); } else { eprintln!("{}", x); }
+            }
+            return Err(format!("{} problem/s found in the settings", problems.len()).into());
+        }
+        if is_check
+        {
+            println!("The settings are valid");
+            return Ok(());
+        }
+    }
+    else
+    {
+        for x in problems.iter().filter(|x| x.is_unreadable())
+        {
+            println!("{}", x);
+        }
+    }
 
-    if let Some(x) = cli.config
+    // the settings are only shown, before any of them is checked
+    if let Some(arguments::Command::Config { action: arguments::ConfigCommand::Show }) = cli.command
     {
-        config::load(&x, &mut args);
+        config::show(&settings, atty::is(atty::Stream::Stdout));
+        return Ok(());
     }
     
     // END HANDLE CONFIG
 
+    // HANDLE JOBS
+
+    // the solvers and hints spread their work over one thread pool,
+    // without --jobs (or with 0) it has a thread for every core
+    if let Some(jobs) = args.jobs
+    {
+        rayon::ThreadPoolBuilder::new().num_threads(jobs).build_global()?;
+    }
+
+    // END HANDLE JOBS
 
     // HANDLE CONFLICTS
     // Program will exit if there are conflicting argument uses
+    if args.daily
+    {
+        if args.random
+        {
+            return Err("Cannot use -r in daily mode".into());
+        }
+        if let Some(_x) = args.word.clone()
+        {
+            return Err("Cannot use -w in daily mode".into());
+        }
+        if let Some(_x) = args.day.clone()
+        {
+            return Err("Cannot use -d in daily mode".into());
+        }
+        if args.evil
+        {
+            return Err("Cannot use --evil in daily mode".into());
+        }
+        if args.boards.unwrap_or(1) > 1
+        {
+            return Err("Cannot use several boards in daily mode".into());
+        }
+    }
+    else if let Some(_x) = args.date.clone()
+    {
+        return Err("Cannot use --date without --daily".into());
+    }
+    if args.evil
+    {
+        if args.random
+        {
+            return Err("Cannot use -r in evil mode".into());
+        }
+        if let Some(_x) = args.word.clone()
+        {
+            return Err("Cannot use -w in evil mode".into());
+        }
+    }
+    // the full-screen ui has no room for the hint list
+    if args.tui && args.hint
+    {
+        return Err("Cannot use --hint with --tui".into());
+    }
+    if args.player.as_deref() == Some("")
+    {
+        return Err("--player needs a name".into());
+    }
+    let boards = args.boards.unwrap_or(1);
+    if boards == 0
+    {
+        return Err("--boards must be at least 1".into());
+    }
+    if boards > 1
+    {
+        if args.evil
+        {
+            return Err("Cannot use --evil with several boards".into());
+        }
+        if args.difficult
+        {
+            return Err("Cannot use -D with several boards".into());
+        }
+        if args.hint
+        {
+            return Err("Cannot use --hint with several boards".into());
+        }
+        if args.tui
+        {
+            return Err("Cannot use --tui with several boards".into());
+        }
+        if let Some(_x) = args.word.clone()
+        {
+            return Err("Cannot use -w with several boards".into());
+        }
+    }
     if args.random
     {
         if let Some(_x) = args.word.clone()
@@ -63,7 +190,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>>
             return Err("Cannot use -w in random mode".into());
         }
     }
-    else
+    // daily mode is random mode with the day taken from the date, -d was refused above
+    else if !args.daily
     {
         if let Some(_x) = args.seed.clone()
         {
@@ -76,11 +204,63 @@ fn main() -> Result<(), Box<dyn std::error::Error>>
     }
     // END HANDLE CONFLICTS
 
+    // HANDLE DAILY
+
+    // the puzzle is picked from the seeded shuffle by the number of days since the
+    // epoch, so everyone playing on the same date with the same seed gets the same word
+    let mut daily: Option<(String, i64)> = None;
+    if args.daily
+    {
+        let today = match args.date.clone()
+        {
+            Some(x) => match NaiveDate::parse_from_str(&x, "%Y-%m-%d")
+            {
+                Ok(date) => date,
+                Err(_) => return Err(format!("Invalid date: {}, use YYYY-MM-DD", x).into()),
+            },
+            None => Local::now().date_naive(),
+        };
+        let (y, m, d) = DAILY_EPOCH;
+        let epoch = NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        let index = (today - epoch).num_days();
+        if index < 0
+        {
+            return Err(format!("There are no daily puzzles before {}", epoch).into());
+        }
+        daily = Some((today.format("%Y-%m-%d").to_string(), index));
+        args.random = true;
+    }
+
+    // END HANDLE DAILY
+
+    // HANDLE WORD LENGTH
+
+    // words default to five letters, other lengths need their own word lists
+    let word_length = args.length.unwrap_or(5);
+    if word_length < 4 || word_length > 11
+    {
+        return Err("Word length must be between 4 and 11".into());
+    }
+    if let Some(x) = args.word.clone()
+    {
+        if x.chars().count() != word_length
+        {
+            return Err("Length of -w does not match the word length".into());
+        }
+    }
+
+    // END HANDLE WORD LENGTH
+
     // HANDLE EXTERNAL FILES
 
-    // switching to vector for easier manipulation
-    let mut final_list: Vec<&str> = builtin_words::FINAL.to_vec(); 
-    let mut acceptable_list: Vec<&str> = builtin_words::ACCEPTABLE.to_vec();
+    // only words of the chosen length are kept from the built-in lists
+    // every list is indexed once here, so looking up a guess does not scan it
+    let builtin_final: Vec<&str> = builtin_words::FINAL.iter()
+        .filter(|w| w.len() == word_length).copied().collect();
+    let builtin_acceptable: Vec<&str> = builtin_words::ACCEPTABLE.iter()
+        .filter(|w| w.len() == word_length).copied().collect();
+    let mut final_list = WordList::new(&builtin_final);
+    let mut acceptable_list = WordList::new(&builtin_acceptable);
 
     // checks if non-default final_set has been provided
     let mut str_f: String = String::new();
@@ -89,19 +269,20 @@ fn main() -> Result<(), Box<dyn std::error::Error>>
         let mut f = File::open(filename)?;
         f.read_to_string(&mut str_f)?;
 
-        let new_final_list: Vec<&str> = str_f.lines().collect();
+        let mut new_final_list: Vec<&str> = str_f.lines().collect();
 
         // checking if the new list satisfies requirements
         // if it is, then the original list is replaced with the new one
 
-        if readfilemode::check_valid_list(&new_final_list, &final_list)
+        if readfilemode::check_word_length(&new_final_list, word_length)
+            && readfilemode::check_valid_list(&new_final_list, &final_list)
         {
-            final_list = new_final_list;
+            new_final_list.sort();
+            final_list = WordList::new(&new_final_list);
         }
         else {
             return Err("Invalid final-set".into());
         }
-        final_list.sort();
     }
 
     // checks if non-default acceptable_set has been provided
@@ -111,23 +292,135 @@ fn main() -> Result<(), Box<dyn std::error::Error>>
         let mut f = File::open(filename)?;
         f.read_to_string(&mut str_a)?;
 
-        let new_acceptable_list: Vec<&str> = str_a.lines().collect();
+        let mut new_acceptable_list: Vec<&str> = str_a.lines().collect();
 
         // checking if the new list satisfies requirements
         // if it is, then the original list is replaced with the new one
 
-        if readfilemode::check_valid_list(&new_acceptable_list, &acceptable_list)
+        if readfilemode::check_word_length(&new_acceptable_list, word_length)
+            && readfilemode::check_valid_list(&new_acceptable_list, &acceptable_list)
         {
-            acceptable_list = new_acceptable_list;
+            new_acceptable_list.sort();
+            acceptable_list = WordList::new(&new_acceptable_list);
         }
         else {
             return Err("Invalid acceptable-set".into());
         }
-        acceptable_list.sort();
+    }
+
+    if final_list.is_empty()
+    {
+        return Err(format!("No {}-letter words available, use --final-set", word_length).into());
     }
 
     // END HANDLE EXTERNAL FILES
 
+    // HANDLE PATTERN CACHE
+
+    // the matrix is keyed by the lists, so it is written and read once they are final
+    if let Some(arguments::Command::Precompute) = cli.command
+    {
+        let path = match patterns::cache_path(&final_list, &acceptable_list)
+        {
+            Some(x) => x,
+            None => return Err("No cache directory, set XDG_CACHE_HOME or HOME".into()),
+        };
+        let (rows, columns) = patterns::build(&final_list, &acceptable_list, &path)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        println!("{} guesses by {} answers written to {}", rows, columns, path.display());
+        return Ok(());
+    }
+    // only the solvers and hints use it, other games do not pay for mapping it
+    let is_solver = matches!(cli.command, Some(arguments::Command::Bench { .. }) | Some(arguments::Command::Openers { .. }));
+    let pattern_matrix = if is_solver || args.hint
+    {
+        match PatternMatrix::open(&final_list, &acceptable_list)
+        {
+            Ok(x) => x,
+            Err(e) =>
+            {
+                eprintln!("Ignoring the pattern cache: {}", e);
+                None
+            }
+        }
+    }
+    else
+    {
+        None
+    };
+
+    // END HANDLE PATTERN CACHE
+
+    // HANDLE SUBCOMMANDS
+
+    // the benchmark plays on its own, so it returns before any game is started
+    if let Some(arguments::Command::Bench { strategy }) = cli.command
+    {
+        let strategy = match solver::Strategy::from_name(&strategy)
+        {
+            Some(x) => x,
+            None => return Err(format!("Unknown strategy: {}", strategy).into()),
+        };
+        bench::run(&final_list, &acceptable_list, args.difficult, strategy, pattern_matrix.as_ref(), atty::is(atty::Stream::Stdout));
+        return Ok(());
+    }
+    if let Some(arguments::Command::Openers { metric, strategy, top, shortlist }) = &cli.command
+    {
+        let metric = match solver::Metric::from_name(metric)
+        {
+            Some(x) => x,
+            None => return Err(format!("Unknown metric: {}", metric).into()),
+        };
+        let strategy = match solver::Strategy::from_name(strategy)
+        {
+            Some(x) => x,
+            None => return Err(format!("Unknown strategy: {}", strategy).into()),
+        };
+        openers::run(&final_list, &acceptable_list, args.difficult, metric, strategy, *top, *shortlist,
+            pattern_matrix.as_ref(), atty::is(atty::Stream::Stdout));
+        return Ok(());
+    }
+    // the statistics are read from the state file, no game is played
+    if let Some(arguments::Command::Stats) = cli.command
+    {
+        match args.state.clone()
+        {
+            Some(x) => stats::run(&x, args.player.as_deref(), atty::is(atty::Stream::Stdout)).map_err(|e| e.to_string())?,
+            None => return Err("stats needs a state file, use --state".into()),
+        }
+        return Ok(());
+    }
+    if let Some(arguments::Command::Leaderboard) = cli.command
+    {
+        match args.state.clone()
+        {
+            Some(x) => stats::leaderboard(&x, atty::is(atty::Stream::Stdout)).map_err(|e| e.to_string())?,
+            None => return Err("leaderboard needs a state file, use --state".into()),
+        }
+        return Ok(());
+    }
+    // games are copied between a json file and the state, no game is played
+    let transfer = match &cli.command
+    {
+        Some(arguments::Command::Import { file }) => Some((file.clone(), true)),
+        Some(arguments::Command::Export { file }) => Some((file.clone(), false)),
+        _ => None,
+    };
+    if let Some((file, is_import)) = transfer
+    {
+        let state = match args.state.clone()
+        {
+            Some(x) => x,
+            None => return Err("import and export need a state file, use --state".into()),
+        };
+        let (from, to) = if is_import { (file, state) } else { (state, file) };
+        let count = progress::copy(&from, &to).map_err(|e| e.to_string())?;
+        println!("{} games copied from {} to {}", count, from, to);
+        return Ok(());
+    }
+
+    // END HANDLE SUBCOMMANDS
+
     // HANDLE PROGESS IN JSON
 
     // Checks if state file exists
@@ -144,6 +437,15 @@ fn main() -> Result<(), Box<dyn std::error::Error>>
         record_progress = true;
     }
 
+    // a daily puzzle can only be played once, an unfinished one can still be resumed
+    if let Some((date, _)) = &daily
+    {
+        if record_progress && progress::has_played(&filename, args.player.as_deref(), date).map_err(|e| e.to_string())?
+        {
+            return Err(format!("The daily puzzle of {} was already played", date).into());
+        }
+    }
+
     // END HANDLE PROGESS IN JSON
 
     // HANDLE RANDOM
@@ -151,12 +453,44 @@ fn main() -> Result<(), Box<dyn std::error::Error>>
     // Obtains seed and day data
 
     let seed = args.seed.unwrap_or(1);
-    let day = args.day.unwrap_or(1);
+    let day = match &daily
+    {
+        Some((_, index)) => (*index as usize % final_list.len()) as i32 + 1,
+        None => args.day.unwrap_or(1),
+    };
+    let date = daily.map(|(date, _)| date);
+    // the boards of the first round take the words of day, day + 1, ...
+    if args.random && (day < 1 || day as usize - 1 + boards > final_list.len())
+    {
+        return Err(format!("Day {} with {} board/s is out of range, the final list has {} words",
+            day, boards, final_list.len()).into());
+    }
 
     // if -r option selected, then the lists will be shuffled using the provided seed
+    // the shuffle scheme pinned in the state file wins, a different one is refused
+    // so that the words of its days do not change
     if args.random  
     {
-        let mut rng = StdRng::seed_from_u64(seed);
+        let pinned = if record_progress { progress::load_shuffle_version(&filename).map_err(|e| e.to_string())? } else { None };
+        let version = match (args.shuffle_version, pinned)
+        {
+            (Some(x), Some(y)) if x != y =>
+                return Err(format!("The state file uses shuffle version {}, not {}", y, x).into()),
+            (Some(x), _) => x,
+            (None, Some(y)) => y,
+            (None, None) => shuffle::DEFAULT_VERSION,
+        };
+        let scheme = match Scheme::from_version(version)
+        {
+            Some(x) => x,
+            None => return Err(format!("Unknown shuffle version: {}", version).into()),
+        };
+        if record_progress && pinned == None && args.shuffle_version != None
+        {
+            progress::pin_shuffle_version(&filename, version).map_err(|e| e.to_string())?;
+        }
+
+        let mut rng = Shuffler::new(scheme, seed);
         final_list.shuffle(&mut rng);
         acceptable_list.shuffle(&mut rng);
     }
@@ -168,9 +502,35 @@ fn main() -> Result<(), Box<dyn std::error::Error>>
     let is_tty = atty::is(atty::Stream::Stdout); 
     
     // essential information is passed into the game
-    game::start(is_tty, &final_list, &acceptable_list, args.word,
-        args.difficult, args.stats, args.random, day,
-        record_progress, filename, cli.hint);
+    let settings = game::Settings
+    {
+        is_tty,
+        final_list: &final_list,
+        acceptable_list: &acceptable_list,
+        patterns: pattern_matrix.as_ref(),
+        word: args.word,
+        difficult: args.difficult,
+        stats: args.stats,
+        random: args.random,
+        seed,
+        day,
+        state: if record_progress { Some(filename) } else { None },
+        player: args.player,
+        hint: args.hint,
+        hint_count: args.hint_count.unwrap_or(5),
+        word_length,
+        evil: args.evil,
+        boards,
+        date,
+    };
+
+    // the full-screen ui needs a terminal, otherwise the normal output is used
+    if args.tui && is_tty
+    {
+        tui::start(&settings).map_err(|e| e.to_string())?;
+        return Ok(());
+    }
+    game::start(&settings);
 
     Ok(())
 }
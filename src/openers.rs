@@ -0,0 +1,94 @@
+use rayon::prelude::*;
+use wordle::engine::Outcome;
+use wordle::patterns::PatternMatrix;
+use wordle::solver::{self, Metric, Solver, Strategy};
+use wordle::WordList;
+
+// average guesses of the games `strategy` plays against every final word when it
+// always opens with `word`, a failed game counts as one guess more than allowed
+fn average_length(word: &str, final_list: &WordList, acceptable_list: &WordList, is_difficult: bool,
+    strategy: Strategy, patterns: Option<&PatternMatrix>) -> f64
+{
+    let mut solver = Solver::new(strategy, final_list, acceptable_list, is_difficult, patterns);
+    solver.set_opener(word);
+    let mut total = 0;
+    for answer in final_list.iter()
+    {
+        let game = solver.play(answer);
+        total += match game.outcome()
+        {
+            Some(Outcome::CORRECT) => game.history().len(),
+            _ => game.max_guesses() + 1,
+        };
+    }
+    return total as f64 / final_list.len() as f64;
+}
+
+// better scores first, then words that could be the answer, then alphabetical
+fn sort(ranked: &mut Vec<(String, bool, f64)>, metric: Metric)
+{
+    ranked.sort_by(|a, b|
+    {
+        let order = if metric.is_lower_better() { a.2.total_cmp(&b.2) } else { b.2.total_cmp(&a.2) };
+        order.then_with(|| b.1.cmp(&a.1)).then_with(|| a.0.cmp(&b.0))
+    });
+}
+
+// ranks every accepted word as the first guess of a round and prints the best `top`
+// the one-guess metrics are worked out for every word, the length metric plays whole
+// games, so it is only worked out for the `shortlist` words with the highest entropy
+// in difficult mode those games keep to the same rules as a player
+// the test friendly version has one line per word: <rank> <word> <score>
+pub fn run(final_list: &WordList, acceptable_list: &WordList, is_difficult: bool, metric: Metric, strategy: Strategy,
+    top: usize, shortlist: usize, patterns: Option<&PatternMatrix>, is_tty: bool)
+{
+    let lookup = patterns.and_then(|p| p.lookup(final_list));
+    let mut pool: Vec<String> = final_list.pairs().chain(acceptable_list.pairs()).map(|(_, upper)| upper.clone()).collect();
+    pool.sort();
+    pool.dedup();
+
+    let first = if metric == Metric::LENGTH { Metric::ENTROPY } else { metric };
+    let mut ranked: Vec<(String, bool, f64)> = pool.into_par_iter().map(|word|
+    {
+        let score = match first
+        {
+            Metric::EXPECTED => solver::expected_remaining(&word, final_list, lookup.as_ref()),
+            Metric::WORST => solver::partition_sizes(&word, final_list, lookup.as_ref()).into_iter().max().unwrap_or(0) as f64,
+            _ => solver::entropy(&word, final_list, lookup.as_ref()),
+        };
+        let is_candidate = final_list.contains(&word);
+        (word, is_candidate, score)
+    }).collect();
+    sort(&mut ranked, first);
+
+    if metric == Metric::LENGTH
+    {
+        ranked.truncate(shortlist.max(top));
+        ranked = ranked.into_par_iter().map(|(word, is_candidate, _)|
+        {
+            let score = average_length(&word, final_list, acceptable_list, is_difficult, strategy, patterns);
+            (word, is_candidate, score)
+        }).collect();
+        sort(&mut ranked, metric);
+    }
+    ranked.truncate(top);
+
+    let score = |x: f64| if metric == Metric::WORST { format!("{}", x) } else { format!("{:.4}", x) };
+    if is_tty
+    {
+        let mode = if is_difficult { " (difficult mode)" } else { "" };
+        let by = if metric == Metric::LENGTH { format!("{} with {}", metric.name(), strategy.name()) } else { metric.name().to_string() };
+        println!("{}\n", console::style(format!("Openers by {}{}", by, mode)).bold().cyan());
+        for (i, (word, _, x)) in ranked.iter().enumerate()
+        {
+            println!("{:>3}  {} {:>10}", console::style(i + 1).bold().cyan(), console::style(word).green(), score(*x));
+        }
+    }
+    else
+    {
+        for (i, (word, _, x)) in ranked.iter().enumerate()
+        {
+            println!("{} {} {}", i + 1, word, score(*x));
+        }
+    }
+}
@@ -0,0 +1,260 @@
+// precomputed pattern matrix: the feedback code of every guess against every answer,
+// written once by `wordle precompute` and memory-mapped by the solvers and hints
+//
+// the file is named after a hash of the word lists, so changing the lists makes
+// the solvers look for another file instead of reading stale codes
+// layout, numbers in little endian:
+//   "WORDLEPM", u32 format version, u32 letters per word, u64 hash of the lists,
+//   u32 rows, u32 columns, the row words, the column words, rows * columns codes
+// the rows are every accepted guess, the columns the answers, both uppercase and
+// sorted so that shuffling the lists does not change the file
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use memmap2::Mmap;
+use crate::feedback::{self, Code, MAX_LETTERS};
+
+const MAGIC: &[u8; 8] = b"WORDLEPM";
+const FORMAT_VERSION: u32 = 1;
+const HEADER_LEN: usize = 32;
+
+pub struct PatternMatrix
+{
+    map: Mmap,
+    rows: HashMap<[u8; MAX_LETTERS], usize>, // row of every guess
+    columns: HashMap<[u8; MAX_LETTERS], usize>, // column of every answer
+    column_count: usize,
+    offset: usize, // start of the codes
+}
+
+// the columns of a set of answers, looked up once and then used for every guess
+pub struct Lookup<'p>
+{
+    matrix: &'p PatternMatrix,
+    columns: Vec<usize>,
+}
+
+// uppercase letters of a word padded with zeros, None for longer words
+fn key(word: &str) -> Option<[u8; MAX_LETTERS]>
+{
+    if word.len() > MAX_LETTERS
+    {
+        return None;
+    }
+    let mut key = [0u8; MAX_LETTERS];
+    for (i, b) in word.bytes().enumerate()
+    {
+        key[i] = b.to_ascii_uppercase();
+    }
+    return Some(key);
+}
+
+// the sorted uppercase words of the rows and the columns
+fn words(final_list: &[&str], acceptable_list: &[&str]) -> (Vec<String>, Vec<String>)
+{
+    let mut rows: Vec<String> = final_list.iter().chain(acceptable_list.iter()).map(|w| w.to_uppercase()).collect();
+    rows.sort();
+    rows.dedup();
+    let mut columns: Vec<String> = final_list.iter().map(|w| w.to_uppercase()).collect();
+    columns.sort();
+    columns.dedup();
+    return (rows, columns);
+}
+
+// the letters per word of the rows, None unless they all have the same length
+// and fit in a code
+fn letters(rows: &[String]) -> Option<usize>
+{
+    let letters = rows.first().map(|w| w.len()).unwrap_or(0);
+    if rows.iter().any(|w| w.len() != letters || w.len() > MAX_LETTERS)
+    {
+        return None;
+    }
+    return Some(letters);
+}
+
+// FNV-1a over both lists, written out so that the hash of a list never changes
+// between builds the way std's hasher may
+fn hash(rows: &[String], columns: &[String]) -> u64
+{
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for word in rows.iter().chain([String::new()].iter()).chain(columns.iter())
+    {
+        for b in word.bytes().chain([b'\n'])
+        {
+            hash ^= b as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    return hash;
+}
+
+// the directory of the cache files, $XDG_CACHE_HOME/wordle
+// XDG_CACHE_HOME defaults to ~/.cache and is ignored unless it is an absolute path
+pub fn cache_dir() -> Option<PathBuf>
+{
+    let cache_home = match std::env::var("XDG_CACHE_HOME")
+    {
+        Ok(x) if Path::new(&x).is_absolute() => PathBuf::from(x),
+        _ => Path::new(&std::env::var("HOME").ok().filter(|x| !x.is_empty())?).join(".cache"),
+    };
+    return Some(cache_home.join("wordle"));
+}
+
+fn path_of(rows: &[String], columns: &[String]) -> Option<PathBuf>
+{
+    return Some(cache_dir()?.join(format!("patterns-{:016x}.bin", hash(rows, columns))));
+}
+
+// where the matrix of these lists is cached
+pub fn cache_path(final_list: &[&str], acceptable_list: &[&str]) -> Option<PathBuf>
+{
+    let (rows, columns) = words(final_list, acceptable_list);
+    return path_of(&rows, &columns);
+}
+
+fn invalid(path: &Path, reason: &str) -> io::Error
+{
+    return io::Error::new(io::ErrorKind::InvalidData, format!("{} {}", path.display(), reason));
+}
+
+// computes the matrix of the lists and writes it to `path`
+// the file is written next to its final name first, so readers never see half of it
+// returns the number of rows and columns
+pub fn build(final_list: &[&str], acceptable_list: &[&str], path: &Path) -> io::Result<(usize, usize)>
+{
+    let (rows, columns) = words(final_list, acceptable_list);
+    let letters = match letters(&rows)
+    {
+        Some(x) => x,
+        None => return Err(io::Error::new(io::ErrorKind::InvalidInput,
+            format!("the pattern matrix needs words of the same length with at most {} letters", MAX_LETTERS))),
+    };
+
+    let mut data: Vec<u8> = Vec::with_capacity(HEADER_LEN + (rows.len() + columns.len()) * letters + rows.len() * columns.len());
+    data.extend_from_slice(MAGIC);
+    data.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+    data.extend_from_slice(&(letters as u32).to_le_bytes());
+    data.extend_from_slice(&hash(&rows, &columns).to_le_bytes());
+    data.extend_from_slice(&(rows.len() as u32).to_le_bytes());
+    data.extend_from_slice(&(columns.len() as u32).to_le_bytes());
+    for word in rows.iter().chain(columns.iter())
+    {
+        data.extend_from_slice(word.as_bytes());
+    }
+    let answers: Vec<&str> = columns.iter().map(|w| w.as_str()).collect();
+    for guess in &rows
+    {
+        data.extend(feedback::codes(guess, &answers));
+    }
+
+    if let Some(dir) = path.parent()
+    {
+        fs::create_dir_all(dir)?;
+    }
+    let temporary = path.with_extension("tmp");
+    let mut file = File::create(&temporary)?;
+    file.write_all(&data)?;
+    file.sync_all()?;
+    fs::rename(&temporary, path)?;
+    return Ok((rows.len(), columns.len()));
+}
+
+// reads the u32 at `at`
+fn number(map: &[u8], at: usize) -> usize
+{
+    let mut bytes = [0u8; 4];
+    bytes.copy_from_slice(&map[at..at + 4]);
+    return u32::from_le_bytes(bytes) as usize;
+}
+
+impl PatternMatrix
+{
+    // maps the cached matrix of the lists, None if it was never precomputed
+    // or the lists could not have one
+    pub fn open(final_list: &[&str], acceptable_list: &[&str]) -> io::Result<Option<PatternMatrix>>
+    {
+        let (rows, columns) = words(final_list, acceptable_list);
+        // every word has a key then, so no two rows or columns share one
+        if letters(&rows).is_none()
+        {
+            return Ok(None);
+        }
+        let path = match path_of(&rows, &columns)
+        {
+            Some(x) => x,
+            None => return Ok(None),
+        };
+        let file = match File::open(&path)
+        {
+            Ok(x) => x,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        // the cache is only ever replaced by a rename, never written in place
+        let map = unsafe { Mmap::map(&file)? };
+
+        if map.len() < HEADER_LEN || &map[..8] != MAGIC || number(&map, 8) != FORMAT_VERSION as usize
+        {
+            return Err(invalid(&path, "is not a pattern cache of this version"));
+        }
+        let letters = number(&map, 12);
+        let row_count = number(&map, 24);
+        let column_count = number(&map, 28);
+        let offset = HEADER_LEN + (row_count + column_count) * letters;
+        if map[16..24] != hash(&rows, &columns).to_le_bytes() || row_count != rows.len() || column_count != columns.len()
+            || map.len() != offset + row_count * column_count
+        {
+            return Err(invalid(&path, "does not match the word lists"));
+        }
+
+        let mut matrix = PatternMatrix { map, rows: HashMap::new(), columns: HashMap::new(), column_count, offset };
+        for (i, word) in rows.iter().enumerate()
+        {
+            matrix.rows.insert(key(word).unwrap(), i);
+        }
+        for (i, word) in columns.iter().enumerate()
+        {
+            matrix.columns.insert(key(word).unwrap(), i);
+        }
+        return Ok(Some(matrix));
+    }
+
+    // the codes of `guess` against every answer, in column order
+    pub fn row(&self, guess: &str) -> Option<&[Code]>
+    {
+        let start = self.offset + self.rows.get(&key(guess)?)? * self.column_count;
+        return Some(&self.map[start..start + self.column_count]);
+    }
+
+    pub fn column(&self, answer: &str) -> Option<usize>
+    {
+        return self.columns.get(&key(answer)?).copied();
+    }
+
+    // the code of a single pair, None if either word is not in the matrix
+    pub fn code(&self, answer: &str, guess: &str) -> Option<Code>
+    {
+        let column = self.column(answer)?;
+        return Some(self.row(guess)?[column]);
+    }
+
+    // looks up the columns of `answers`, None if one of them is missing
+    pub fn lookup(&self, answers: &[&str]) -> Option<Lookup<'_>>
+    {
+        let columns: Option<Vec<usize>> = answers.iter().map(|a| self.column(a)).collect();
+        return Some(Lookup { matrix: self, columns: columns? });
+    }
+}
+
+impl<'p> Lookup<'p>
+{
+    // the codes of `guess` against the answers of the lookup, None without a row for it
+    pub fn codes(&self, guess: &str) -> Option<Vec<Code>>
+    {
+        let row = self.matrix.row(guess)?;
+        return Some(self.columns.iter().map(|c| row[*c]).collect());
+    }
+}
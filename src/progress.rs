@@ -1,138 +1,546 @@
 use serde::{Deserialize, Serialize};
-use serde_json::Result;
-use std::fs::File;
-use std::io::{BufReader, BufWriter, Read};
-use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Write};
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fmt;
+use std::path::Path;
+use chrono::{DateTime, Local, SecondsFormat};
+use crate::sqlite;
 
 // struct GameData and Game used to store information in the required format
 // for json storage
 
+// a state given as sqlite:<path> is kept in a SQLite database instead of a json file
+pub const SQLITE_PREFIX: &str = "sqlite:";
+
+fn database(filename: &str) -> Option<&str>
+{
+    return filename.strip_prefix(SQLITE_PREFIX);
+}
+
+// version of the layout below, written to every file
+// version 1 had no schema_version field and only stored answer and guesses per game
+// version 3 added the histories of named players
+pub const SCHEMA_VERSION: u32 = 3;
+
+fn legacy_version() -> u32
+{
+    return 1;
+}
+
+// the outcome of a saved game
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum SavedOutcome
+{
+    CORRECT,
+    FAILED,
+}
+
+impl From<wordle::Outcome> for SavedOutcome
+{
+    fn from(outcome: wordle::Outcome) -> SavedOutcome
+    {
+        match outcome
+        {
+            wordle::Outcome::CORRECT => SavedOutcome::CORRECT,
+            wordle::Outcome::FAILED => SavedOutcome::FAILED,
+        }
+    }
+}
+
+// the options a round was played with
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Mode
+{
+    #[serde(default)]
+    pub difficult: bool,
+    #[serde(default)]
+    pub hint: bool,
+    #[serde(default)]
+    pub evil: bool,
+    // seed and day of a random round
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub day: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub length: Option<usize>,
+    // the date of a daily puzzle, YYYY-MM-DD
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Game 
 {
-    answer: String,
-    guesses: Vec<String>,
+    pub answer: String,
+    pub guesses: Vec<String>,
+    // every answer of a multi-board game, empty for a single board
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub answers: Vec<String>,
+    // always set from version 2 on, older games get it when they are migrated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outcome: Option<SavedOutcome>,
+    // RFC 3339 timestamps and the seconds in between, unknown for migrated games
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finished: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_secs: Option<i64>,
+    #[serde(flatten)]
+    pub mode: Mode,
+}
+
+// a round that was not finished yet, saved after every guess so that
+// it can be resumed by the next launch with the same state file
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Unfinished
+{
+    pub answers: Vec<String>, // in evil mode the answer it would currently reveal
+    pub guesses: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started: Option<String>,
+    #[serde(flatten)]
+    pub mode: Mode,
+}
+
+// the history of one player
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Profile
+{
+    #[serde(default)]
+    pub total_rounds: i32,
+    #[serde(default)]
+    pub games: Vec<Game>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unfinished: Option<Unfinished>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct GameData 
 {
-    total_rounds: i32,
-    games: Vec<Game>,
+    #[serde(default = "legacy_version")]
+    pub schema_version: u32,
+    // the history played without --player, kept at the top as in older files
+    #[serde(flatten)]
+    pub history: Profile,
+    // the histories of the players given with --player
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub players: BTreeMap<String, Profile>,
+    // the shuffle scheme the random rounds of this file were played with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shuffle_version: Option<u32>,
 }
 
-// reads data in a json file
-pub fn read(filename: &str) -> Result<GameData> 
+impl Default for GameData
 {
-    let file = File::open(filename);
-    match file 
+    fn default() -> GameData
     {
-        Ok(f) => 
-        {
-            let reader = BufReader::new(f);
-            let game_data = serde_json::from_reader(reader)?;
-            return Ok(game_data)
-        }
-        Err(_) => panic!("read() error")
+        GameData { schema_version: SCHEMA_VERSION, history: Profile::default(), players: BTreeMap::new(), shuffle_version: None }
     }
 }
 
-// load data in the json file
-pub fn load(filename: &str, results: &mut Vec<(crate::game::Outcome, i32)>, word_count: &mut HashMap<String, i32>, total_rounds: &mut i32)
+impl GameData
 {
-    let mut file = match File::open(filename) 
+    // the history of `player`, None stands for the history played without --player
+    pub fn profile(&mut self, player: Option<&str>) -> &mut Profile
     {
-        Ok(file) => file,
-        Err(err) => {
-            print!("Failed to open file {}: {}", filename, err);
-            return;
+        match player
+        {
+            Some(x) => self.players.entry(x.to_string()).or_default(),
+            None => &mut self.history,
         }
-    };
-
-    // begin process to check if json file is empty
-    let mut contents = String::new();
+    }
 
-    if let Err(err) = file.read_to_string(&mut contents) 
+    // every history with at least one game, the one played without --player first
+    pub fn profiles(&self) -> Vec<(Option<&str>, &Profile)>
     {
-        print!("Failed to read file {}: {}", filename, err);
-        return;
-    }
-    
-    // checks that json file is not empty
-    if contents.trim() == "{}" {
-        return; 
+        let named = self.players.iter().map(|(name, profile)| (Some(name.as_str()), profile));
+        return std::iter::once((None, &self.history)).chain(named).filter(|(_, x)| !x.games.is_empty()).collect();
     }
+}
 
-    let game_data: std::result::Result<GameData, _> = serde_json::from_str(&contents);
-
-    // if the json file format is correct, the function pushes all past information
-    // into results vector
-    // function also uploads information about word counts
-    match game_data
+// brings data of an older schema version up to date
+fn migrate(data: &mut GameData)
+{
+    // version 2 to 3 only added fields, version 1 to 2: the outcome was inferred from the guesses
+    // and the word length from the answer
+    if data.schema_version < 2
     {
-        Ok(data) => 
+        for game in data.history.games.iter_mut()
         {
-            *total_rounds = data.total_rounds;
-    
-            for game in &data.games
+            let won = if !game.answers.is_empty()
             {
-                let total_guesses = game.guesses.len();
-                if game.answer == game.guesses[total_guesses - 1]
-                {
-                    results.push((crate::game::Outcome::CORRECT, total_guesses as i32))
-                }
-                else 
-                {
-                    results.push((crate::game::Outcome::FAILED, total_guesses as i32))
-                }
+                // a multi-board game is won if every answer was guessed
+                game.answers.iter().all(|a| game.guesses.contains(a))
             }
-            for game in &data.games
+            else
             {
-                for word in &game.guesses
-                {
-                    let x = word_count.entry(word.clone()).or_insert(0);
-                    *x += 1;
-                }
-            }
+                game.guesses.last() == Some(&game.answer)
+            };
+            game.outcome = Some(if won { SavedOutcome::CORRECT } else { SavedOutcome::FAILED });
+            game.mode.length = Some(game.answer.chars().count());
+        }
+    }
+    data.schema_version = SCHEMA_VERSION;
+}
+
+// the current time as an RFC 3339 timestamp
+pub fn timestamp() -> String
+{
+    return Local::now().to_rfc3339_opts(SecondsFormat::Secs, false);
+}
+
+// used to represent why the state file could not be used
+#[allow(non_camel_case_types)]
+#[derive(Debug)]
+pub enum ProgressError
+{
+    IO(io::Error),
+    FORMAT(serde_json::Error),
+    VERSION(u32), // written by a newer version of the program
+    SQLITE(rusqlite::Error),
+    NOT_EMPTY(String), // games would be copied into a state that already has some
+}
+
+impl fmt::Display for ProgressError
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        match self
+        {
+            ProgressError::IO(e) => write!(f, "{}", e),
+            ProgressError::FORMAT(e) => write!(f, "invalid state file: {}", e),
+            ProgressError::VERSION(v) => write!(f, "state file has schema version {}, only {} and older are supported", v, SCHEMA_VERSION),
+            ProgressError::SQLITE(e) => write!(f, "database error: {}", e),
+            ProgressError::NOT_EMPTY(x) => write!(f, "{} already has saved games, nothing was copied", x),
         }
-        Err(_) => { panic!("Invalid file format") }
     }
 }
 
+impl Error for ProgressError {}
+
+impl From<io::Error> for ProgressError
+{
+    fn from(e: io::Error) -> ProgressError
+    {
+        ProgressError::IO(e)
+    }
+}
+
+impl From<serde_json::Error> for ProgressError
+{
+    fn from(e: serde_json::Error) -> ProgressError
+    {
+        ProgressError::FORMAT(e)
+    }
+}
+
+impl From<rusqlite::Error> for ProgressError
+{
+    fn from(e: rusqlite::Error) -> ProgressError
+    {
+        ProgressError::SQLITE(e)
+    }
+}
+
+// reads data in a json file
+pub fn read(filename: &str) -> Result<GameData, ProgressError> 
+{
+    let reader = BufReader::new(File::open(filename)?);
+    let mut game_data: GameData = serde_json::from_reader(reader)?;
+    if game_data.schema_version > SCHEMA_VERSION
+    {
+        return Err(ProgressError::VERSION(game_data.schema_version));
+    }
+    migrate(&mut game_data);
+    return Ok(game_data);
+}
+
 // writes data into json file
-pub fn write(filename: &str, game_data: &GameData) -> Result<()> 
+// the data goes to a temporary file first which then replaces the old file,
+// so a crash in the middle of writing never leaves a truncated history behind
+pub fn write(filename: &str, game_data: &GameData) -> Result<(), ProgressError> 
 {
-    let file = File::create(filename);
-    match file
+    let temp = format!("{}.{}.tmp", filename, std::process::id());
+    let result = write_to(&temp, game_data).and_then(|_| Ok(fs::rename(&temp, filename)?));
+    if result.is_err()
     {
-        Ok(f) =>
+        let _ = fs::remove_file(&temp);
+    }
+    return result;
+}
+
+fn write_to(filename: &str, game_data: &GameData) -> Result<(), ProgressError>
+{
+    let mut writer = BufWriter::new(File::create(filename)?);
+    serde_json::to_writer_pretty(&mut writer, game_data)?;
+    writer.flush()?;
+    writer.get_ref().sync_all()?;
+    return Ok(());
+}
+
+// runs a read-modify-write of the state file while holding an advisory lock on
+// <filename>.lock, sessions sharing a file wait for each other and every change
+// is applied to the latest data, so concurrent rounds are merged instead of lost
+fn modify<T>(filename: &str, change: impl FnOnce(&mut GameData) -> T) -> Result<T, ProgressError>
+{
+    let lock = File::create(format!("{}.lock", filename))?;
+    lock.lock()?;
+    let mut data = read_or_new(filename)?;
+    let result = change(&mut data);
+    write(filename, &data)?;
+    return Ok(result);
+}
+
+// moves a corrupt state file aside to <filename>.<timestamp>.bak and returns the new name
+pub fn backup(filename: &str) -> Result<String, ProgressError>
+{
+    let backup = format!("{}.{}.bak", filename, Local::now().format("%Y%m%d-%H%M%S"));
+    fs::rename(filename, &backup)?;
+    return Ok(backup);
+}
+
+// reads the saved data, a missing file starts out empty
+// a file that is not valid is backed up first, so its history is not overwritten
+fn read_or_new(filename: &str) -> Result<GameData, ProgressError>
+{
+    if !Path::new(filename).exists()
+    {
+        return Ok(GameData::default());
+    }
+    match read(filename) 
+    {
+        Ok(data) => Ok(data),
+        Err(ProgressError::FORMAT(e)) =>
         {
-            let writer = BufWriter::new(f);
-            serde_json::to_writer_pretty(writer, game_data)?;
-            return Ok(())
+            let moved_to = backup(filename)?;
+            eprintln!("State file {} is not valid ({}), it was moved to {} and new statistics are started",
+                filename, e, moved_to);
+            Ok(GameData::default())
         }
-        Err(_) => panic!("write() error")
+        Err(e) => Err(e),
+    }
+}
+
+// reads all saved data of a json file or a database
+pub fn read_state(filename: &str) -> Result<GameData, ProgressError>
+{
+    match database(filename)
+    {
+        Some(path) => sqlite::read(path),
+        None => read_or_new(filename),
+    }
+}
+
+// copies every history of the state `from` into the state `to`, which must not have any games yet
+// either side can be a json file or a database, returns the number of games copied
+pub fn copy(from: &str, to: &str) -> Result<usize, ProgressError>
+{
+    if !Path::new(database(from).unwrap_or(from)).exists()
+    {
+        return Err(io::Error::new(io::ErrorKind::NotFound, format!("{} does not exist", from)).into());
+    }
+    let data = match database(from)
+    {
+        Some(path) => sqlite::read(path)?,
+        None => read(from)?,
+    };
+    if !read_state(to)?.profiles().is_empty()
+    {
+        return Err(ProgressError::NOT_EMPTY(to.to_string()));
     }
+    let count = data.profiles().iter().map(|(_, x)| x.games.len()).sum();
+    match database(to)
+    {
+        Some(path) => sqlite::replace(path, &data)?,
+        None => modify(to, |x| *x = data)?,
+    }
+    return Ok(count);
+}
+
+// the players with a history in the state, None stands for the history played without --player
+pub fn players(filename: &str) -> Result<Vec<Option<String>>, ProgressError>
+{
+    return Ok(read_state(filename)?.profiles().iter().map(|(name, _)| name.map(|x| x.to_string())).collect());
 }
 
-// adds individual round into data
-pub fn update(filename: &str, gue: &Vec<String>, total_rounds: &mut i32, ans: String)
+// load data in the json file, only the history of `player` is used
+pub fn load(filename: &str, player: Option<&str>, results: &mut Vec<(wordle::Outcome, i32)>, word_count: &mut HashMap<String, i32>,
+    total_rounds: &mut i32) -> Result<(), ProgressError>
 {
-    let mut data = match read(filename) 
+    let mut state = read_state(filename)?;
+    let data = state.profile(player);
+
+    // the function pushes all past information into results vector
+    // function also uploads information about word counts
+    *total_rounds = data.total_rounds;
+
+    for game in &data.games
     {
-        Ok(data) => data,
-        Err(_) => GameData
+        let total_guesses = game.guesses.len();
+        if game.outcome == Some(SavedOutcome::CORRECT)
         {
-            games: Vec::new(),
-            total_rounds: 0,
-        },
+            results.push((wordle::Outcome::CORRECT, total_guesses as i32))
+        }
+        else 
+        {
+            results.push((wordle::Outcome::FAILED, total_guesses as i32))
+        }
+    }
+    for game in &data.games
+    {
+        for word in &game.guesses
+        {
+            let x = word_count.entry(word.clone()).or_insert(0);
+            *x += 1;
+        }
+    }
+    return Ok(());
+}
+
+// returns the unfinished round of `player`, if there is one
+pub fn load_unfinished(filename: &str, player: Option<&str>) -> Result<Option<Unfinished>, ProgressError>
+{
+    if let Some(path) = database(filename)
+    {
+        return sqlite::load_unfinished(path, player);
+    }
+    return Ok(read_or_new(filename)?.profile(player).unfinished.take());
+}
+
+// checks whether `player` has already finished the daily puzzle of `date`
+pub fn has_played(filename: &str, player: Option<&str>, date: &str) -> Result<bool, ProgressError>
+{
+    if let Some(path) = database(filename)
+    {
+        return sqlite::has_played(path, player, date);
+    }
+    return Ok(read_or_new(filename)?.profile(player).games.iter().any(|g| g.mode.date.as_deref() == Some(date)));
+}
+
+// returns the shuffle version pinned in the state file, if there is one
+pub fn load_shuffle_version(filename: &str) -> Result<Option<u32>, ProgressError>
+{
+    if let Some(path) = database(filename)
+    {
+        return sqlite::load_shuffle_version(path);
+    }
+    return Ok(read_or_new(filename)?.shuffle_version);
+}
+
+// pins the shuffle version so later random rounds keep the same word order
+pub fn pin_shuffle_version(filename: &str, version: u32) -> Result<(), ProgressError>
+{
+    if let Some(path) = database(filename)
+    {
+        return sqlite::pin_shuffle_version(path, version);
+    }
+    return modify(filename, |data| data.shuffle_version = Some(version));
+}
+
+// stores the round `player` is playing, None removes it
+pub fn save_unfinished(filename: &str, player: Option<&str>, unfinished: Option<Unfinished>) -> Result<(), ProgressError>
+{
+    if let Some(path) = database(filename)
+    {
+        return sqlite::save_unfinished(path, player, &unfinished);
+    }
+    return modify(filename, |data| data.profile(player).unfinished = unfinished);
+}
+
+// saves the round `player` is in the middle of, called after every accepted guess
+// so that an interrupted round can be resumed by the next launch
+pub fn save_round(filename: &str, player: Option<&str>, answers: Vec<String>, guesses: Vec<String>, started: &str, mode: &Mode)
+-> Result<(), ProgressError>
+{
+    return save_unfinished(filename, player, Some(Unfinished
+    {
+        answers,
+        guesses,
+        started: Some(started.to_string()),
+        mode: mode.clone(),
+    }));
+}
+
+// the unfinished round of `player`, if it can be played with words of `word_length` letters
+// a state that cannot be read has no round to resume, the error shows up when saving
+pub fn resumable(filename: &str, player: Option<&str>, word_length: usize) -> Option<Unfinished>
+{
+    let unfinished = load_unfinished(filename, player).ok().flatten()?;
+    if unfinished.answers.iter().all(|a| a.chars().count() == word_length)
+    {
+        return Some(unfinished);
+    }
+    return None;
+}
+
+// records an unfinished round the player gave up as a lost game with the guesses
+// made so far, so that restarting does not give a free retry
+pub fn give_up(filename: &str, player: Option<&str>, unfinished: Unfinished, results: &mut Vec<(wordle::Outcome, i32)>,
+    word_count: &mut HashMap<String, i32>, total_rounds: &mut i32) -> Result<(), ProgressError>
+{
+    results.push((wordle::Outcome::FAILED, unfinished.guesses.len() as i32));
+    for word in &unfinished.guesses
+    {
+        *word_count.entry(word.clone()).or_insert(0) += 1;
+    }
+    *total_rounds += 1;
+    let started = unfinished.started.unwrap_or_else(timestamp);
+    return update(filename, player, &unfinished.guesses, total_rounds, unfinished.answers, wordle::Outcome::FAILED,
+        started, unfinished.mode);
+}
+
+// adds individual round into the history of `player`, `ans` holds one answer per board
+// `started` is the timestamp the round began at, it ends now
+// the round is appended to the rounds other sessions may have saved in the meantime,
+// `total_rounds` is updated to the merged count
+pub fn update(filename: &str, player: Option<&str>, gue: &Vec<String>, total_rounds: &mut i32, ans: Vec<String>, outcome: wordle::Outcome,
+    started: String, mode: Mode) -> Result<(), ProgressError>
+{
+    let finished = Local::now();
+    let duration_secs = DateTime::parse_from_rfc3339(&started).ok()
+        .map(|s| (finished.fixed_offset() - s).num_seconds().max(0));
+
+    let answer = ans.first().cloned().unwrap_or_default();
+    let answers = if ans.len() > 1 { ans } else { Vec::new() };
+    let game: Game = Game
+    {
+        answer,
+        guesses: gue.clone(),
+        answers,
+        outcome: Some(outcome.into()),
+        started: Some(started),
+        finished: Some(finished.to_rfc3339_opts(SecondsFormat::Secs, false)),
+        duration_secs,
+        mode,
     };
-    
-    let game: Game = Game {answer: ans, guesses: gue.clone()};
-    data.games.push(game);
 
-    data.total_rounds = *total_rounds;
+    if let Some(path) = database(filename)
+    {
+        *total_rounds = sqlite::add_game(path, player, &game)?;
+        return Ok(());
+    }
+
+    *total_rounds = modify(filename, |state|
+    {
+        let data = state.profile(player);
+        // the round is finished, so it is no longer kept as unfinished
+        // unless the unfinished round belongs to another session
+        if let Some(x) = &data.unfinished
+        {
+            if gue.starts_with(&x.guesses)
+            {
+                data.unfinished = None;
+            }
+        }
 
-    let _ = write(filename, &data);
-    
-}  
\ No newline at end of file
+        data.games.push(game);
+
+        data.total_rounds += 1;
+        data.total_rounds
+    })?;
+    return Ok(());
+}
@@ -1,18 +1,18 @@
-use std::collections::HashMap;
+use std::collections::HashSet;
+use wordle::WordList;
 
 // checks if a file is valid
-pub fn check_valid_list(new: &Vec<&str>, old: &Vec<&str>) -> bool
+pub fn check_valid_list(new: &Vec<&str>, old: &WordList) -> bool
 {
     let mut new: Vec<String> = new.iter().map(|s| s.to_uppercase()).collect();
-    let mut old: Vec<String> = old.iter().map(|s| s.to_uppercase()).collect();
-
     new.sort();
-    old.sort();
 
-    // using binary search to see if new list is a subset of old one
-    for element in &new
+    // looking up every word in the index of the old list to see if the new list is a subset
+    // an empty old list means there are no built-in words of this length,
+    // so there is nothing to compare against
+    for element in new.iter().filter(|_| !old.is_empty())
     {
-        if !old.binary_search(element).is_ok()
+        if !old.contains(element)
         {
             print!("not found: {}", element);
             return false;
@@ -20,17 +20,30 @@ pub fn check_valid_list(new: &Vec<&str>, old: &Vec<&str>) -> bool
     }
 
     //checks that no values are repeated
-    let mut element_count: HashMap<String, i32> = HashMap::new();
+    let mut seen: HashSet<&str> = HashSet::new();
 
     for element in &new
     {
-        let x = element_count.entry(element.to_string()).or_insert(0);
-        *x += 1;
-        if *x > 1
+        if !seen.insert(element)
         {
             print!("repeated: {}", element);
             return false;
         }
     }
     return true;
-}
\ No newline at end of file
+}
+
+// checks that every word in a list has the requested number of letters
+// and is made up of alphabetic characters only
+pub fn check_word_length(list: &Vec<&str>, word_length: usize) -> bool
+{
+    for element in list
+    {
+        if element.chars().count() != word_length || !element.chars().all(|c| c.is_ascii_alphabetic())
+        {
+            print!("wrong length: {}", element);
+            return false;
+        }
+    }
+    return true;
+}
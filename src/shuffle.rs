@@ -0,0 +1,177 @@
+// seeded shuffling of the word lists
+//
+// the order of the shuffled lists decides which word belongs to a seed and day,
+// so the algorithms below are written out in full instead of borrowing them from
+// a random number crate, whose generators may change between releases
+// a scheme must never change once it is released, new behaviour gets a new version
+
+// the shuffle algorithms, identified in config and state files by their version
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Scheme
+{
+    // version 1, the order every seed had before the schemes were versioned:
+    // ChaCha with 12 rounds, keyed by a PCG32 expansion of the seed, and a
+    // Fisher-Yates shuffle that draws indices with Lemire's multiply method
+    CHACHA12,
+    // version 2, SplitMix64 and a Fisher-Yates shuffle that draws indices
+    // with Lemire's exact rejection method, short enough to port anywhere
+    SPLITMIX64,
+}
+
+// the scheme used when no version is pinned
+pub const DEFAULT_VERSION: u32 = 1;
+
+impl Scheme
+{
+    pub fn from_version(version: u32) -> Option<Scheme>
+    {
+        match version
+        {
+            1 => Some(Scheme::CHACHA12),
+            2 => Some(Scheme::SPLITMIX64),
+            _ => None,
+        }
+    }
+
+    pub fn version(&self) -> u32
+    {
+        match self
+        {
+            Scheme::CHACHA12 => 1,
+            Scheme::SPLITMIX64 => 2,
+        }
+    }
+}
+
+// a seeded generator, lists shuffled one after another with the same
+// generator continue from where the previous shuffle stopped
+pub struct Shuffler
+{
+    scheme: Scheme,
+    state: u64, // SplitMix64 state
+    key: [u32; 8], // ChaCha key
+    counter: u64, // ChaCha block counter
+    buffer: [u32; 16], // the current ChaCha block
+    index: usize, // next unused word of the buffer
+}
+
+impl Shuffler
+{
+    pub fn new(scheme: Scheme, seed: u64) -> Shuffler
+    {
+        // the ChaCha key is filled with PCG32 outputs, advancing the state first
+        let mut state = seed;
+        let mut key = [0u32; 8];
+        for word in key.iter_mut()
+        {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(11634580027462260723);
+            let xorshifted = (((state >> 18) ^ state) >> 27) as u32;
+            *word = xorshifted.rotate_right((state >> 59) as u32);
+        }
+
+        Shuffler { scheme, state: seed, key, counter: 0, buffer: [0; 16], index: 16 }
+    }
+
+    // Fisher-Yates: from the back, every position is swapped with a random
+    // position at or before it
+    pub fn shuffle<T>(&mut self, list: &mut [T])
+    {
+        for i in (1..list.len()).rev()
+        {
+            let j = self.below(i as u64 + 1) as usize;
+            list.swap(i, j);
+        }
+    }
+
+    // a uniformly random number in 0..bound
+    fn below(&mut self, bound: u64) -> u64
+    {
+        match self.scheme
+        {
+            Scheme::CHACHA12 =>
+            {
+                // 32 bit draws, rejecting the low products above a conservative zone
+                let range = bound as u32;
+                let zone = (range << range.leading_zeros()).wrapping_sub(1);
+                loop
+                {
+                    let product = self.next_chacha() as u64 * range as u64;
+                    if product as u32 <= zone
+                    {
+                        return product >> 32;
+                    }
+                }
+            }
+            Scheme::SPLITMIX64 =>
+            {
+                // 64 bit draws, rejecting exactly the low products that would bias the result
+                let threshold = bound.wrapping_neg() % bound;
+                loop
+                {
+                    let product = self.next_splitmix() as u128 * bound as u128;
+                    if product as u64 >= threshold
+                    {
+                        return (product >> 64) as u64;
+                    }
+                }
+            }
+        }
+    }
+
+    fn next_splitmix(&mut self) -> u64
+    {
+        self.state = self.state.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        return z ^ (z >> 31);
+    }
+
+    fn next_chacha(&mut self) -> u32
+    {
+        if self.index == 16
+        {
+            self.buffer = chacha12_block(&self.key, self.counter);
+            self.counter = self.counter.wrapping_add(1);
+            self.index = 0;
+        }
+        self.index += 1;
+        return self.buffer[self.index - 1];
+    }
+}
+
+fn quarter_round(s: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize)
+{
+    s[a] = s[a].wrapping_add(s[b]); s[d] = (s[d] ^ s[a]).rotate_left(16);
+    s[c] = s[c].wrapping_add(s[d]); s[b] = (s[b] ^ s[c]).rotate_left(12);
+    s[a] = s[a].wrapping_add(s[b]); s[d] = (s[d] ^ s[a]).rotate_left(8);
+    s[c] = s[c].wrapping_add(s[d]); s[b] = (s[b] ^ s[c]).rotate_left(7);
+}
+
+// one 64 byte ChaCha block with a 64 bit block counter and a zero nonce
+fn chacha12_block(key: &[u32; 8], counter: u64) -> [u32; 16]
+{
+    let mut input = [0u32; 16];
+    input[..4].copy_from_slice(&[0x61707865, 0x3320646e, 0x79622d32, 0x6b206574]); // "expand 32-byte k"
+    input[4..12].copy_from_slice(key);
+    input[12] = counter as u32;
+    input[13] = (counter >> 32) as u32;
+
+    let mut s = input;
+    for _ in 0..6 // 12 rounds, a column and a diagonal round at a time
+    {
+        quarter_round(&mut s, 0, 4, 8, 12);
+        quarter_round(&mut s, 1, 5, 9, 13);
+        quarter_round(&mut s, 2, 6, 10, 14);
+        quarter_round(&mut s, 3, 7, 11, 15);
+        quarter_round(&mut s, 0, 5, 10, 15);
+        quarter_round(&mut s, 1, 6, 11, 12);
+        quarter_round(&mut s, 2, 7, 8, 13);
+        quarter_round(&mut s, 3, 4, 9, 14);
+    }
+    for i in 0..16
+    {
+        s[i] = s[i].wrapping_add(input[i]);
+    }
+    return s;
+}
@@ -0,0 +1,357 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use rayon::prelude::*;
+use crate::engine::{self, Game, Status};
+use crate::feedback::{self, Code};
+use crate::patterns::{Lookup, PatternMatrix};
+use crate::wordlist::WordList;
+
+// a word suggested in hint mode together with how much it is expected to tell
+#[derive(Clone, Debug, PartialEq)]
+pub struct Suggestion
+{
+    pub word: String,
+    pub score: f64, // expected information gain in bits
+    pub is_candidate: bool, // the suggestion could itself be the answer
+}
+
+// checks if a word is still a possible solution: guessing `word` against `e`
+// must give exactly the same statuses the player received
+pub fn check_valid_hint(status: &[Status], e: &str, word: String) -> bool
+{
+    let (e_status, _) = engine::compare(&e.to_string(), word);
+    if status == e_status
+    {
+        return true;
+    }
+    return false
+}
+
+// keeps only the candidates that would have produced `status` for `word`
+pub fn filter_candidates<'b>(candidates: &[&'b str], status: &[Status], word: &String) -> Vec<&'b str>
+{
+    // words that fit in a feedback code are compared without allocating
+    if word.len() <= feedback::MAX_LETTERS
+    {
+        let target: Code = feedback::encode(status);
+        return candidates.iter().filter(|e| feedback::code(e, word) == target).copied().collect();
+    }
+
+    let mut temp: Vec<&str> = Vec::new();
+    for e in candidates
+    {
+        if check_valid_hint(status, &e.to_uppercase(), word.clone())
+        {
+            temp.push(e);
+        }
+    }
+    return temp;
+}
+
+// groups the candidates by the status pattern `guess` would receive against them
+// and returns the size of every group
+// with a `lookup` of the candidates the codes are read from the precomputed pattern matrix
+pub fn partition_sizes(guess: &String, candidates: &[&str], lookup: Option<&Lookup>) -> Vec<usize>
+{
+    // words that fit in a feedback code are counted in a fixed array, one slot per code
+    if guess.len() <= feedback::MAX_LETTERS
+    {
+        let codes = match lookup.and_then(|l| l.codes(guess))
+        {
+            Some(x) => x,
+            None => feedback::codes(guess, candidates),
+        };
+        let mut counts = [0usize; feedback::CODES];
+        for code in codes
+        {
+            counts[code as usize] += 1;
+        }
+        let mut sizes: Vec<usize> = counts.into_iter().filter(|n| *n > 0).collect();
+        sizes.sort();
+        return sizes;
+    }
+
+    let mut buckets: HashMap<Vec<Status>, usize> = HashMap::new();
+    for e in candidates
+    {
+        let (status, _) = engine::compare(&e.to_uppercase(), guess.clone());
+        *buckets.entry(status).or_insert(0) += 1;
+    }
+    // sorted so that floating point sums do not depend on hash order
+    let mut sizes: Vec<usize> = buckets.into_values().collect();
+    sizes.sort();
+    return sizes;
+}
+
+// expected number of candidates still left after guessing `guess` when every
+// candidate is equally likely to be the answer
+pub fn expected_remaining(guess: &String, candidates: &[&str], lookup: Option<&Lookup>) -> f64
+{
+    let total = candidates.len() as f64;
+    let mut remaining = 0.0;
+    for size in partition_sizes(guess, candidates, lookup)
+    {
+        remaining += (size * size) as f64 / total;
+    }
+    return remaining;
+}
+
+// expected information (in bits) gained by guessing `guess` when every
+// candidate is equally likely to be the answer
+pub fn entropy(guess: &String, candidates: &[&str], lookup: Option<&Lookup>) -> f64
+{
+    let total = candidates.len() as f64;
+    let mut bits = 0.0;
+    for size in partition_sizes(guess, candidates, lookup)
+    {
+        let p = size as f64 / total;
+        bits -= p * p.log2();
+    }
+    return bits;
+}
+
+// the uppercase words worth scoring as the next guess, `candidates` are uppercase and sorted
+// with one or two candidates left the best move is simply to guess one of them
+fn shortlist(guesses: &[&str], candidates: &[String]) -> Vec<String>
+{
+    if candidates.len() <= 2
+    {
+        return candidates.to_vec();
+    }
+    let mut pool: Vec<String> = guesses.iter().map(|s| s.to_uppercase()).collect();
+    pool.sort();
+    pool.dedup();
+    return pool;
+}
+
+// ranks every word in `guesses` by its entropy over the remaining candidates
+// ties are broken in favour of words that could be the answer, then alphabetically
+pub fn rank_guesses(guesses: &[&str], candidates: &[&str], top: usize, patterns: Option<&PatternMatrix>) -> Vec<Suggestion>
+{
+    let lookup = patterns.and_then(|p| p.lookup(candidates));
+    let mut candidate_words: Vec<String> = candidates.iter().map(|s| s.to_uppercase()).collect();
+    candidate_words.sort();
+
+    let pool = shortlist(guesses, &candidate_words);
+
+    // every guess is scored on its own, spread over the thread pool
+    let mut ranked: Vec<Suggestion> = pool.into_par_iter().map(|word|
+    {
+        Suggestion
+        {
+            score: entropy(&word, candidates, lookup.as_ref()),
+            is_candidate: candidate_words.binary_search(&word).is_ok(),
+            word,
+        }
+    }).collect();
+
+    ranked.sort_by(|a, b|
+    {
+        b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| b.is_candidate.cmp(&a.is_candidate))
+            .then_with(|| a.word.cmp(&b.word))
+    });
+    ranked.truncate(top);
+    return ranked;
+}
+
+// the built-in strategies a solver can use to pick its next guess
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Strategy
+{
+    NAIVE, // first remaining candidate in alphabetical order
+    ENTROPY, // highest expected information gain
+    MINIMAX, // smallest worst-case group of remaining candidates
+}
+
+impl Strategy
+{
+    pub fn from_name(name: &str) -> Option<Strategy>
+    {
+        match name.to_lowercase().as_str()
+        {
+            "naive" => Some(Strategy::NAIVE),
+            "entropy" => Some(Strategy::ENTROPY),
+            "minimax" => Some(Strategy::MINIMAX),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str
+    {
+        match self
+        {
+            Strategy::NAIVE => "naive",
+            Strategy::ENTROPY => "entropy",
+            Strategy::MINIMAX => "minimax",
+        }
+    }
+}
+
+// the ways an opening guess can be scored
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Metric
+{
+    EXPECTED, // expected number of candidates left
+    ENTROPY, // expected information gain
+    WORST, // size of the largest group of candidates left
+    LENGTH, // average guesses of whole games played by a strategy, failures count as one more than allowed
+}
+
+impl Metric
+{
+    pub fn from_name(name: &str) -> Option<Metric>
+    {
+        match name.to_lowercase().as_str()
+        {
+            "expected" => Some(Metric::EXPECTED),
+            "entropy" => Some(Metric::ENTROPY),
+            "worst" => Some(Metric::WORST),
+            "length" => Some(Metric::LENGTH),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str
+    {
+        match self
+        {
+            Metric::EXPECTED => "expected",
+            Metric::ENTROPY => "entropy",
+            Metric::WORST => "worst",
+            Metric::LENGTH => "length",
+        }
+    }
+
+    // entropy is the only metric where a higher score is better
+    pub fn is_lower_better(&self) -> bool
+    {
+        return *self != Metric::ENTROPY;
+    }
+}
+
+// picks the next guess out of `pool` for the remaining candidates
+pub fn choose_guess(strategy: Strategy, pool: &[&str], candidates: &[&str], patterns: Option<&PatternMatrix>) -> String
+{
+    let mut candidate_words: Vec<String> = candidates.iter().map(|s| s.to_uppercase()).collect();
+    candidate_words.sort();
+
+    if candidate_words.is_empty()
+    {
+        return pool.first().map(|s| s.to_uppercase()).unwrap_or_default();
+    }
+    if strategy == Strategy::NAIVE
+    {
+        return candidate_words[0].clone();
+    }
+
+    match strategy
+    {
+        Strategy::MINIMAX =>
+        {
+            let lookup = patterns.and_then(|p| p.lookup(candidates));
+            // the worst cases are worked out on the thread pool, the best one is picked in pool order
+            let worst_cases: Vec<(String, usize)> = shortlist(pool, &candidate_words).into_par_iter().map(|word|
+            {
+                let worst = partition_sizes(&word, candidates, lookup.as_ref()).into_iter().max().unwrap_or(0);
+                (word, worst)
+            }).collect();
+            let mut best: Option<(usize, bool, String)> = None;
+            for (word, worst) in worst_cases
+            {
+                let is_candidate = candidate_words.binary_search(&word).is_ok();
+                // smaller worst case wins, then words that could be the answer, then alphabetical
+                let better = match &best
+                {
+                    None => true,
+                    Some((w, c, b)) => worst < *w || (worst == *w && (is_candidate && !*c || (is_candidate == *c && word < *b))),
+                };
+                if better
+                {
+                    best = Some((worst, is_candidate, word));
+                }
+            }
+            match best
+            {
+                Some((_, _, word)) => word,
+                None => candidate_words[0].clone(),
+            }
+        }
+        _ =>
+        {
+            match rank_guesses(pool, candidates, 1, patterns).into_iter().next()
+            {
+                Some(s) => s.word,
+                None => candidate_words[0].clone(),
+            }
+        }
+    }
+}
+
+// plays rounds with a fixed strategy, remembering which guess it picked after
+// each sequence of feedback so that common openings are only computed once
+// rounds can be played from several threads at once, they share what is remembered
+pub struct Solver<'a>
+{
+    strategy: Strategy,
+    final_list: &'a WordList<'a>,
+    acceptable_list: &'a WordList<'a>,
+    is_difficult: bool,
+    patterns: Option<&'a PatternMatrix>,
+    memo: Mutex<HashMap<Vec<Vec<Status>>, String>>,
+}
+
+impl<'a> Solver<'a>
+{
+    // `patterns` is the precomputed matrix of the lists, if there is one
+    pub fn new(strategy: Strategy, final_list: &'a WordList<'a>, acceptable_list: &'a WordList<'a>, is_difficult: bool,
+        patterns: Option<&'a PatternMatrix>) -> Solver<'a>
+    {
+        Solver { strategy, final_list, acceptable_list, is_difficult, patterns, memo: Mutex::new(HashMap::new()) }
+    }
+
+    // makes every round start with `word` instead of the strategy's own opening guess
+    pub fn set_opener(&mut self, word: &str)
+    {
+        self.memo.get_mut().unwrap_or_else(|e| e.into_inner()).insert(Vec::new(), word.to_uppercase());
+    }
+
+    // plays a whole round against `answer` and returns the finished game
+    // guesses are taken from the answer list, which keeps every round fast
+    // the guess after a sequence of feedback never depends on the answer, so rounds
+    // played in any order and on any thread give the same games
+    pub fn play(&self, answer: &str) -> Game<'a>
+    {
+        let mut game = Game::new(answer, self.final_list, self.acceptable_list, self.is_difficult);
+        let mut candidates: Vec<&str> = self.final_list.to_vec();
+        let mut patterns: Vec<Vec<Status>> = Vec::new();
+
+        while !game.is_over()
+        {
+            let remembered = self.memo.lock().unwrap_or_else(|e| e.into_inner()).get(&patterns).cloned();
+            let guess = match remembered
+            {
+                Some(word) => word.clone(),
+                None =>
+                {
+                    // DIFFICULT MODE START
+                    let pool: Vec<&str> = self.final_list.pairs()
+                        .filter(|(_, upper)| game.meets_constraints(upper)).map(|(w, _)| w).collect();
+                    // DIFFICULT MODE END
+                    let word = choose_guess(self.strategy, &pool, &candidates, self.patterns);
+                    self.memo.lock().unwrap_or_else(|e| e.into_inner()).insert(patterns.clone(), word.clone());
+                    word
+                }
+            };
+
+            let feedback = match game.guess(&guess)
+            {
+                Ok(feedback) => feedback,
+                Err(_) => break,
+            };
+            candidates = filter_candidates(&candidates, &feedback.statuses, &feedback.word);
+            patterns.push(feedback.statuses);
+        }
+        return game;
+    }
+}
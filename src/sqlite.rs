@@ -0,0 +1,348 @@
+use rusqlite::{params, Connection, OptionalExtension, TransactionBehavior};
+use std::collections::HashMap;
+use std::time::Duration;
+use crate::progress::{Game, GameData, Mode, Profile, ProgressError, SavedOutcome, Unfinished, SCHEMA_VERSION};
+
+// the SQLite backend of the state, used for --state sqlite:<path>
+// every operation only touches the rows it needs and runs in its own transaction,
+// so a round costs a few inserts instead of rewriting the whole history
+// the history played without --player is stored under the empty player name
+
+const SCHEMA: &str = "
+CREATE TABLE meta
+(
+    key TEXT PRIMARY KEY,
+    value INTEGER NOT NULL
+);
+CREATE TABLE players
+(
+    name TEXT PRIMARY KEY,
+    total_rounds INTEGER NOT NULL
+);
+CREATE TABLE games
+(
+    id INTEGER PRIMARY KEY,
+    player TEXT NOT NULL DEFAULT '',
+    answer TEXT NOT NULL,
+    outcome TEXT NOT NULL,
+    started TEXT,
+    finished TEXT,
+    duration_secs INTEGER,
+    difficult INTEGER NOT NULL,
+    hint INTEGER NOT NULL,
+    evil INTEGER NOT NULL,
+    seed INTEGER,
+    day INTEGER,
+    length INTEGER,
+    date TEXT
+);
+CREATE INDEX games_player ON games (player, date);
+CREATE TABLE guesses
+(
+    game_id INTEGER NOT NULL REFERENCES games (id),
+    position INTEGER NOT NULL,
+    word TEXT NOT NULL,
+    PRIMARY KEY (game_id, position)
+);
+CREATE TABLE answers
+(
+    game_id INTEGER NOT NULL REFERENCES games (id),
+    board INTEGER NOT NULL,
+    word TEXT NOT NULL,
+    PRIMARY KEY (game_id, board)
+);
+CREATE TABLE unfinished
+(
+    player TEXT PRIMARY KEY,
+    data TEXT NOT NULL
+);
+";
+
+// version 2 kept a single history, its rounds count and unfinished round move to the empty player name
+const MIGRATE_3: &str = "
+ALTER TABLE games ADD COLUMN player TEXT NOT NULL DEFAULT '';
+DROP INDEX IF EXISTS games_date;
+CREATE INDEX games_player ON games (player, date);
+CREATE TABLE players
+(
+    name TEXT PRIMARY KEY,
+    total_rounds INTEGER NOT NULL
+);
+INSERT INTO players (name, total_rounds) SELECT '', value FROM meta WHERE key = 'total_rounds';
+DELETE FROM meta WHERE key = 'total_rounds';
+ALTER TABLE unfinished RENAME TO unfinished_2;
+CREATE TABLE unfinished
+(
+    player TEXT PRIMARY KEY,
+    data TEXT NOT NULL
+);
+INSERT INTO unfinished (player, data) SELECT '', data FROM unfinished_2;
+DROP TABLE unfinished_2;
+";
+
+// opens the database, creating the tables of a new one and migrating an older one
+// sessions sharing the database wait for each other's transactions
+fn open(path: &str) -> Result<Connection, ProgressError>
+{
+    let mut conn = Connection::open(path)?;
+    conn.busy_timeout(Duration::from_secs(30))?;
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let exists: bool = tx.query_row("SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'meta')",
+        [], |row| row.get(0))?;
+    if !exists
+    {
+        tx.execute_batch(SCHEMA)?;
+        set_meta(&tx, "schema_version", Some(SCHEMA_VERSION as i64))?;
+    }
+    let version = get_meta(&tx, "schema_version")?.unwrap_or(0) as u32;
+    if version > SCHEMA_VERSION
+    {
+        return Err(ProgressError::VERSION(version));
+    }
+    if version < 3
+    {
+        tx.execute_batch(MIGRATE_3)?;
+    }
+    set_meta(&tx, "schema_version", Some(SCHEMA_VERSION as i64))?;
+    tx.commit()?;
+    return Ok(conn);
+}
+
+// the name a player is stored under
+fn name(player: Option<&str>) -> &str
+{
+    return player.unwrap_or("");
+}
+
+fn total_rounds(conn: &Connection, player: Option<&str>) -> rusqlite::Result<i64>
+{
+    let total: Option<i64> = conn.query_row("SELECT total_rounds FROM players WHERE name = ?1", params![name(player)],
+        |row| row.get(0)).optional()?;
+    return Ok(total.unwrap_or(0));
+}
+
+fn set_total_rounds(conn: &Connection, player: Option<&str>, total: i64) -> rusqlite::Result<()>
+{
+    conn.execute("INSERT OR REPLACE INTO players (name, total_rounds) VALUES (?1, ?2)", params![name(player), total])?;
+    return Ok(());
+}
+
+fn get_meta(conn: &Connection, key: &str) -> rusqlite::Result<Option<i64>>
+{
+    return conn.query_row("SELECT value FROM meta WHERE key = ?1", params![key], |row| row.get(0)).optional();
+}
+
+fn set_meta(conn: &Connection, key: &str, value: Option<i64>) -> rusqlite::Result<()>
+{
+    match value
+    {
+        Some(x) => conn.execute("INSERT OR REPLACE INTO meta (key, value) VALUES (?1, ?2)", params![key, x])?,
+        None => conn.execute("DELETE FROM meta WHERE key = ?1", params![key])?,
+    };
+    return Ok(());
+}
+
+fn outcome_name(outcome: Option<SavedOutcome>) -> &'static str
+{
+    match outcome
+    {
+        Some(SavedOutcome::CORRECT) => "CORRECT",
+        _ => "FAILED",
+    }
+}
+
+// the words of every game in one of the word tables, in order
+fn words(conn: &Connection, query: &str) -> rusqlite::Result<HashMap<i64, Vec<String>>>
+{
+    let mut words: HashMap<i64, Vec<String>> = HashMap::new();
+    let mut statement = conn.prepare(query)?;
+    let rows = statement.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?;
+    for row in rows
+    {
+        let (id, word) = row?;
+        words.entry(id).or_default().push(word);
+    }
+    return Ok(words);
+}
+
+fn unfinished(conn: &Connection, player: Option<&str>) -> Result<Option<Unfinished>, ProgressError>
+{
+    let data: Option<String> = conn.query_row("SELECT data FROM unfinished WHERE player = ?1", params![name(player)],
+        |row| row.get(0)).optional()?;
+    return match data
+    {
+        Some(x) => Ok(Some(serde_json::from_str(&x)?)),
+        None => Ok(None),
+    };
+}
+
+fn set_unfinished(conn: &Connection, player: Option<&str>, unfinished: &Option<Unfinished>) -> Result<(), ProgressError>
+{
+    match unfinished
+    {
+        Some(x) => conn.execute("INSERT OR REPLACE INTO unfinished (player, data) VALUES (?1, ?2)",
+            params![name(player), serde_json::to_string(x)?])?,
+        None => conn.execute("DELETE FROM unfinished WHERE player = ?1", params![name(player)])?,
+    };
+    return Ok(());
+}
+
+fn insert_game(conn: &Connection, player: Option<&str>, game: &Game) -> rusqlite::Result<()>
+{
+    let mode = &game.mode;
+    conn.execute("INSERT INTO games (player, answer, outcome, started, finished, duration_secs, difficult, hint, evil, seed, day, length, date)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+        params![name(player), game.answer, outcome_name(game.outcome), game.started, game.finished, game.duration_secs,
+            mode.difficult, mode.hint, mode.evil, mode.seed.map(|x| x as i64), mode.day, mode.length.map(|x| x as i64), mode.date])?;
+    let id = conn.last_insert_rowid();
+    for (i, word) in game.guesses.iter().enumerate()
+    {
+        conn.execute("INSERT INTO guesses (game_id, position, word) VALUES (?1, ?2, ?3)", params![id, i as i64, word])?;
+    }
+    for (i, word) in game.answers.iter().enumerate()
+    {
+        conn.execute("INSERT INTO answers (game_id, board, word) VALUES (?1, ?2, ?3)", params![id, i as i64, word])?;
+    }
+    return Ok(());
+}
+
+// the player a stored name belongs to
+fn player(name: &str) -> Option<&str>
+{
+    return if name.is_empty() { None } else { Some(name) };
+}
+
+// reads everything stored in the database
+pub fn read(path: &str) -> Result<GameData, ProgressError>
+{
+    let conn = open(path)?;
+    let mut data = GameData::default();
+    data.shuffle_version = get_meta(&conn, "shuffle_version")?.map(|x| x as u32);
+
+    let mut statement = conn.prepare("SELECT name, total_rounds FROM players")?;
+    let rows = statement.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)))?;
+    for row in rows
+    {
+        let (name, total_rounds) = row?;
+        data.profile(player(&name)).total_rounds = total_rounds as i32;
+    }
+    let mut statement = conn.prepare("SELECT player, data FROM unfinished")?;
+    let rows = statement.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?;
+    for row in rows
+    {
+        let (name, unfinished) = row?;
+        data.profile(player(&name)).unfinished = Some(serde_json::from_str(&unfinished)?);
+    }
+
+    let mut guesses = words(&conn, "SELECT game_id, word FROM guesses ORDER BY game_id, position")?;
+    let mut answers = words(&conn, "SELECT game_id, word FROM answers ORDER BY game_id, board")?;
+    let mut statement = conn.prepare("SELECT id, player, answer, outcome, started, finished, duration_secs,
+        difficult, hint, evil, seed, day, length, date FROM games ORDER BY id")?;
+    let rows = statement.query_map([], |row|
+    {
+        let outcome: String = row.get(3)?;
+        let game = Game
+        {
+            answer: row.get(2)?,
+            guesses: Vec::new(),
+            answers: Vec::new(),
+            outcome: Some(if outcome == "CORRECT" { SavedOutcome::CORRECT } else { SavedOutcome::FAILED }),
+            started: row.get(4)?,
+            finished: row.get(5)?,
+            duration_secs: row.get(6)?,
+            mode: Mode
+            {
+                difficult: row.get(7)?,
+                hint: row.get(8)?,
+                evil: row.get(9)?,
+                seed: row.get::<_, Option<i64>>(10)?.map(|x| x as u64),
+                day: row.get(11)?,
+                length: row.get::<_, Option<i64>>(12)?.map(|x| x as usize),
+                date: row.get(13)?,
+            },
+        };
+        Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, game))
+    })?;
+    for row in rows
+    {
+        let (id, name, mut game) = row?;
+        game.guesses = guesses.remove(&id).unwrap_or_default();
+        game.answers = answers.remove(&id).unwrap_or_default();
+        data.profile(player(&name)).games.push(game);
+    }
+    return Ok(data);
+}
+
+// replaces everything stored in the database with `data`
+pub fn replace(path: &str, data: &GameData) -> Result<(), ProgressError>
+{
+    let mut conn = open(path)?;
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    tx.execute_batch("DELETE FROM guesses; DELETE FROM answers; DELETE FROM games; DELETE FROM players; DELETE FROM unfinished;")?;
+    let named = data.players.iter().map(|(name, profile)| (Some(name.as_str()), profile));
+    for (player, profile) in std::iter::once((None, &data.history)).chain(named)
+    {
+        write_profile(&tx, player, profile)?;
+    }
+    set_meta(&tx, "shuffle_version", data.shuffle_version.map(|x| x as i64))?;
+    tx.commit()?;
+    return Ok(());
+}
+
+fn write_profile(conn: &Connection, player: Option<&str>, profile: &Profile) -> Result<(), ProgressError>
+{
+    for game in &profile.games
+    {
+        insert_game(conn, player, game)?;
+    }
+    set_total_rounds(conn, player, profile.total_rounds as i64)?;
+    set_unfinished(conn, player, &profile.unfinished)?;
+    return Ok(());
+}
+
+pub fn load_unfinished(path: &str, player: Option<&str>) -> Result<Option<Unfinished>, ProgressError>
+{
+    return unfinished(&open(path)?, player);
+}
+
+pub fn has_played(path: &str, player: Option<&str>, date: &str) -> Result<bool, ProgressError>
+{
+    let conn = open(path)?;
+    return Ok(conn.query_row("SELECT EXISTS (SELECT 1 FROM games WHERE player = ?1 AND date = ?2)",
+        params![name(player), date], |row| row.get(0))?);
+}
+
+pub fn load_shuffle_version(path: &str) -> Result<Option<u32>, ProgressError>
+{
+    return Ok(get_meta(&open(path)?, "shuffle_version")?.map(|x| x as u32));
+}
+
+pub fn pin_shuffle_version(path: &str, version: u32) -> Result<(), ProgressError>
+{
+    set_meta(&open(path)?, "shuffle_version", Some(version as i64))?;
+    return Ok(());
+}
+
+pub fn save_unfinished(path: &str, player: Option<&str>, unfinished: &Option<Unfinished>) -> Result<(), ProgressError>
+{
+    return set_unfinished(&open(path)?, player, unfinished);
+}
+
+// appends a finished game of `player`, see progress::update, and returns their new number of rounds
+pub fn add_game(path: &str, player: Option<&str>, game: &Game) -> Result<i32, ProgressError>
+{
+    let mut conn = open(path)?;
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    if let Some(x) = unfinished(&tx, player)?
+    {
+        if game.guesses.starts_with(&x.guesses)
+        {
+            set_unfinished(&tx, player, &None)?;
+        }
+    }
+    insert_game(&tx, player, game)?;
+    let total_rounds = total_rounds(&tx, player)? + 1;
+    set_total_rounds(&tx, player, total_rounds)?;
+    tx.commit()?;
+    return Ok(total_rounds as i32);
+}
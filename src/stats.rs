@@ -0,0 +1,154 @@
+use std::collections::HashMap;
+use crate::game;
+use crate::progress;
+use wordle::engine;
+use wordle::Outcome;
+
+// counts the won games by number of attempts, distribution[i] holds the games
+// won in i+1 guesses; there are never fewer than MAX_GUESSES buckets
+pub fn distribution(results: &Vec<(Outcome, i32)>) -> Vec<i32>
+{
+    let mut distribution: Vec<i32> = vec![0; engine::MAX_GUESSES];
+    for (outcome, attempts) in results
+    {
+        if *outcome != Outcome::CORRECT || *attempts < 1
+        {
+            continue;
+        }
+        let i = *attempts as usize - 1;
+        if i >= distribution.len()
+        {
+            distribution.resize(i + 1, 0);
+        }
+        distribution[i] += 1;
+    }
+    return distribution;
+}
+
+// returns the current streak (wins since the last loss) and the longest streak
+pub fn streaks(results: &Vec<(Outcome, i32)>) -> (i32, i32)
+{
+    let mut current = 0;
+    let mut longest = 0;
+    for (outcome, _) in results
+    {
+        if *outcome == Outcome::CORRECT
+        {
+            current += 1;
+            if current > longest
+            {
+                longest = current;
+            }
+        }
+        else
+        {
+            current = 0;
+        }
+    }
+    return (current, longest);
+}
+
+// percentage of games won, 0 when nothing has been played
+pub fn win_rate(results: &Vec<(Outcome, i32)>) -> f32
+{
+    let (wins, _, _) = game::summary(results);
+    if results.is_empty()
+    {
+        return 0.0;
+    }
+    return wins as f32 * 100.0 / results.len() as f32;
+}
+
+// prints played games, win %, streaks and the guess histogram for a terminal
+pub fn print_tty(results: &Vec<(Outcome, i32)>)
+{
+    let (current, longest) = streaks(results);
+    println!("{} {}", console::style("Played:").cyan(), console::style(results.len()).cyan());
+    println!("{} {}", console::style("Win %:").cyan(), console::style(format!("{:.0}", win_rate(results))).cyan());
+    println!("{} {}", console::style("Current streak:").cyan(), console::style(current).cyan());
+    println!("{} {}", console::style("Max streak:").cyan(), console::style(longest).cyan());
+
+    println!("\n{}\n", console::style("Guess distribution:").bold().blink().cyan());
+    let distribution = distribution(results);
+    let most = distribution.iter().copied().max().unwrap_or(0).max(1);
+    for (i, n) in distribution.iter().enumerate()
+    {
+        let bar = "#".repeat(((*n as f32 / most as f32) * 30.0).ceil() as usize);
+        println!("{} {} {}", console::style(i + 1).cyan(), console::style(bar).green(), n);
+    }
+}
+
+// prints the statistics of `player` stored in a progress file without playing
+// the test friendly version has three lines:
+// <played> <wins> <losses> <win %>
+// <current streak> <max streak>
+// <games won in 1 guess> <in 2 guesses> ...
+pub fn run(progress_file: &str, player: Option<&str>, is_tty: bool) -> Result<(), progress::ProgressError>
+{
+    let mut results: Vec<(Outcome, i32)> = Vec::new();
+    let mut word_count: HashMap<String, i32> = HashMap::new();
+    let mut total_rounds: i32 = 0;
+    progress::load(progress_file, player, &mut results, &mut word_count, &mut total_rounds)?;
+
+    if is_tty
+    {
+        let (x, y, z) = game::summary(&results);
+        println!("{}\n", console::style("Statistics:").bold().blink().cyan());
+        println!("{} {}", console::style("Wins:").cyan(), console::style(x).cyan());
+        println!("{} {}", console::style("Losses:").cyan(), console::style(y).cyan());
+        println!("{} {}", console::style("Avg. tries:").cyan(), console::style(z).cyan());
+        print_tty(&results);
+    }
+    else
+    {
+        let (x, y, _) = game::summary(&results);
+        let (current, longest) = streaks(&results);
+        println!("{} {} {} {:.2}", results.len(), x, y, win_rate(&results));
+        println!("{} {}", current, longest);
+        let counts: Vec<String> = distribution(&results).iter().map(|n| n.to_string()).collect();
+        println!("{}", counts.join(" "));
+    }
+    return Ok(());
+}
+
+// ranks every player of a progress file by win %, then by fewer average guesses
+// and then by the longer max streak, games played without --player are listed as "-"
+// the test friendly version has one line per player:
+// <rank> <name> <played> <win %> <avg. guesses> <max streak>
+pub fn leaderboard(progress_file: &str, is_tty: bool) -> Result<(), progress::ProgressError>
+{
+    let mut rows: Vec<(String, usize, f32, f32, i32)> = Vec::new();
+    for player in progress::players(progress_file)?
+    {
+        let mut results: Vec<(Outcome, i32)> = Vec::new();
+        let mut word_count: HashMap<String, i32> = HashMap::new();
+        let mut total_rounds: i32 = 0;
+        progress::load(progress_file, player.as_deref(), &mut results, &mut word_count, &mut total_rounds)?;
+        let (_, _, average) = game::summary(&results);
+        let (_, longest) = streaks(&results);
+        rows.push((player.unwrap_or("-".to_string()), results.len(), win_rate(&results), average, longest));
+    }
+    rows.sort_by(|a, b|
+    {
+        b.2.total_cmp(&a.2).then_with(|| a.3.total_cmp(&b.3)).then_with(|| b.4.cmp(&a.4)).then_with(|| a.0.cmp(&b.0))
+    });
+
+    if is_tty
+    {
+        println!("{}\n", console::style("Leaderboard:").bold().blink().cyan());
+        println!("{}", console::style(format!("{:>3}  {:<16} {:>6} {:>6} {:>10} {:>10}",
+            "#", "Player", "Played", "Win %", "Avg. tries", "Max streak")).cyan());
+        for (i, (name, played, rate, average, longest)) in rows.iter().enumerate()
+        {
+            println!("{:>3}  {:<16} {:>6} {:>6.0} {:>10.2} {:>10}", console::style(i + 1).bold().cyan(), name, played, rate, average, longest);
+        }
+    }
+    else
+    {
+        for (i, (name, played, rate, average, longest)) in rows.iter().enumerate()
+        {
+            println!("{} {} {} {:.2} {:.2} {}", i + 1, name, played, rate, average, longest);
+        }
+    }
+    return Ok(());
+}
@@ -0,0 +1,465 @@
+use std::collections::HashMap;
+use std::io;
+use std::thread;
+use std::time::Duration;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Alignment, Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+use wordle::engine::{Game, Outcome, Status};
+use wordle::WordList;
+use crate::{game, progress, stats};
+
+// full-screen frontend: a board grid, an on-screen keyboard coloured with the
+// merged alphabet and a statistics window at the end of every round
+// it is only used when stdout is a terminal, the test-friendly output is untouched
+
+const KEYBOARD: [&str; 3] = ["QWERTYUIOP", "ASDFGHJKL", "ZXCVBNM"];
+const ROWS: usize = 6; // rows of the grid that are visible at once
+const REVEAL_DELAY: Duration = Duration::from_millis(180);
+
+// what the screen is showing right now
+struct View<'a, 'b>
+{
+    game: &'b Game<'a>,
+    input: &'b str,
+    revealed: usize, // tiles of the newest guess already turned over
+    message: &'b str,
+    alphabet: [Status; 26], // keyboard colours, only updated once a guess is revealed
+}
+
+// colour of a tile or key for a status
+fn status_style(status: Status) -> Style
+{
+    match status
+    {
+        Status::G => Style::default().fg(Color::Black).bg(Color::Green).add_modifier(Modifier::BOLD),
+        Status::Y => Style::default().fg(Color::Black).bg(Color::Yellow).add_modifier(Modifier::BOLD),
+        Status::R => Style::default().fg(Color::White).bg(Color::DarkGray),
+        Status::X => Style::default().fg(Color::White),
+    }
+}
+
+fn tile(c: char, style: Style) -> Span<'static>
+{
+    Span::styled(format!(" {} ", c), style)
+}
+
+// builds the rows of the grid: past guesses, the word being typed and empty rows
+fn grid_lines(view: &View) -> Vec<Line<'static>>
+{
+    let history = view.game.history();
+    let length = view.game.word_length();
+    let empty = Style::default().fg(Color::DarkGray);
+    let mut lines: Vec<Line> = Vec::new();
+
+    // in evil mode there is no guess limit, so only the newest rows are shown
+    let first = history.len().saturating_sub(ROWS - 1);
+    for (n, feedback) in history.iter().enumerate().skip(first)
+    {
+        let is_newest = n + 1 == history.len();
+        let mut spans: Vec<Span> = Vec::new();
+        for (i, c) in feedback.word.chars().enumerate()
+        {
+            if is_newest && i >= view.revealed
+            {
+                spans.push(tile(c, Style::default().add_modifier(Modifier::BOLD)));
+            }
+            else
+            {
+                spans.push(tile(c, status_style(feedback.statuses[i])));
+            }
+            spans.push(Span::raw(" "));
+        }
+        lines.push(Line::from(spans));
+        lines.push(Line::from(""));
+    }
+
+    let rows = view.game.max_guesses().min(ROWS).max(lines.len() / 2 + 1);
+    if !view.game.is_over() && lines.len() / 2 < rows
+    {
+        let mut spans: Vec<Span> = Vec::new();
+        let typed: Vec<char> = view.input.chars().collect();
+        for i in 0..length
+        {
+            match typed.get(i)
+            {
+                Some(c) => spans.push(tile(*c, Style::default().add_modifier(Modifier::BOLD))),
+                None => spans.push(tile('_', empty)),
+            }
+            spans.push(Span::raw(" "));
+        }
+        lines.push(Line::from(spans));
+        lines.push(Line::from(""));
+    }
+    while lines.len() / 2 < rows
+    {
+        let mut spans: Vec<Span> = Vec::new();
+        for _ in 0..length
+        {
+            spans.push(tile('_', empty));
+            spans.push(Span::raw(" "));
+        }
+        lines.push(Line::from(spans));
+        lines.push(Line::from(""));
+    }
+    return lines;
+}
+
+// builds the on-screen keyboard coloured with the merged alphabet
+fn keyboard_lines(view: &View) -> Vec<Line<'static>>
+{
+    let alphabet = view.alphabet;
+    let mut lines: Vec<Line> = Vec::new();
+    for row in KEYBOARD
+    {
+        let mut spans: Vec<Span> = Vec::new();
+        for c in row.chars()
+        {
+            spans.push(tile(c, status_style(alphabet[(c as usize) - ('A' as usize)])));
+            spans.push(Span::raw(" "));
+        }
+        lines.push(Line::from(spans));
+        lines.push(Line::from(""));
+    }
+    lines.push(Line::from(Span::styled("ENTER to guess, BACKSPACE to delete, ESC to quit",
+        Style::default().fg(Color::DarkGray))));
+    return lines;
+}
+
+// a rectangle of the given size in the middle of `area`
+fn centered(area: Rect, width: u16, height: u16) -> Rect
+{
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect::new(area.x + (area.width - width) / 2, area.y + (area.height - height) / 2, width, height)
+}
+
+fn draw(frame: &mut Frame, view: &View)
+{
+    let area = frame.area();
+    let [title, board, message, keys] = Layout::vertical([
+        Constraint::Length(2),
+        Constraint::Length((ROWS * 2) as u16),
+        Constraint::Length(2),
+        Constraint::Min(7),
+    ]).areas(area);
+
+    frame.render_widget(Paragraph::new(Line::from(Span::styled("WORDLE",
+        Style::default().fg(Color::Blue).add_modifier(Modifier::BOLD)))).alignment(Alignment::Center), title);
+    frame.render_widget(Paragraph::new(grid_lines(view)).alignment(Alignment::Center), board);
+    frame.render_widget(Paragraph::new(Line::from(Span::styled(view.message.to_string(),
+        Style::default().fg(Color::Red)))).alignment(Alignment::Center), message);
+    frame.render_widget(Paragraph::new(keyboard_lines(view)).alignment(Alignment::Center), keys);
+}
+
+// draws the statistics window on top of the finished board
+fn draw_stats(frame: &mut Frame, view: &View, results: &Vec<(Outcome, i32)>, word_count: &HashMap<String, i32>, is_daily: bool)
+{
+    draw(frame, view);
+
+    let (wins, losses, average) = game::summary(results);
+    let (current, longest) = stats::streaks(results);
+    let mut sorted_word_count: Vec<(&String, &i32)> = word_count.iter().collect();
+    sorted_word_count.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+
+    let headline = if view.game.is_won()
+    {
+        format!("You won in {} tries", view.game.history().len())
+    }
+    else
+    {
+        format!("You lost. Correct answer: {}", view.game.answer())
+    };
+    let mut lines: Vec<Line> = vec![
+        Line::from(Span::styled(headline, Style::default().fg(Color::Blue).add_modifier(Modifier::BOLD))),
+        Line::from(""),
+        Line::from(format!("Wins: {}   Losses: {}   Avg. tries: {:.2}", wins, losses, average)),
+        Line::from(format!("Played: {}   Win %: {:.0}", results.len(), stats::win_rate(results))),
+        Line::from(format!("Current streak: {}   Max streak: {}", current, longest)),
+        Line::from(""),
+    ];
+    let distribution = stats::distribution(results);
+    let most = distribution.iter().copied().max().unwrap_or(0).max(1);
+    for (i, n) in distribution.iter().enumerate()
+    {
+        let bar = "#".repeat(((*n as f32 / most as f32) * 24.0).ceil() as usize);
+        lines.push(Line::from(format!("{} {:<24} {:>3}", i + 1, bar, n)));
+    }
+    lines.push(Line::from(""));
+    for (word, count) in sorted_word_count.iter().take(5)
+    {
+        lines.push(Line::from(format!("{} used {} time/s", word, count)));
+    }
+    lines.push(Line::from(""));
+    let prompt = if is_daily { "Come back tomorrow! (any key)" } else { "Play again? (Y/N)" };
+    lines.push(Line::from(Span::styled(prompt, Style::default().fg(Color::Cyan))));
+
+    let popup = centered(frame.area(), 44, lines.len() as u16 + 2);
+    frame.render_widget(Clear, popup);
+    frame.render_widget(Paragraph::new(lines).alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL).title(" Statistics ")), popup);
+}
+
+// waits for the next key press, None means the player wants to quit
+fn next_key() -> io::Result<Option<KeyCode>>
+{
+    loop
+    {
+        if let Event::Key(key) = event::read()?
+        {
+            if key.kind != KeyEventKind::Press { continue; }
+            if key.code == KeyCode::Esc { return Ok(None); }
+            return Ok(Some(key.code));
+        }
+    }
+}
+
+// asks whether an unfinished round should be resumed or given up, None if the player quit
+fn ask_resume(terminal: &mut DefaultTerminal, guesses: usize) -> io::Result<Option<bool>>
+{
+    loop
+    {
+        terminal.draw(|frame|
+        {
+            let lines = vec![
+                Line::from(Span::styled(format!("An unfinished round with {} guess/es was found", guesses), Style::default().fg(Color::Blue))),
+                Line::from(""),
+                Line::from("Resume it (Y) or give it up (N)?"),
+            ];
+            let popup = centered(frame.area(), 52, 5);
+            frame.render_widget(Paragraph::new(lines).alignment(Alignment::Center)
+                .block(Block::default().borders(Borders::ALL)), popup);
+        })?;
+
+        match next_key()?
+        {
+            None => return Ok(None),
+            Some(KeyCode::Char('y')) | Some(KeyCode::Char('Y')) => return Ok(Some(true)),
+            Some(KeyCode::Char('n')) | Some(KeyCode::Char('N')) => return Ok(Some(false)),
+            _ => {}
+        }
+    }
+}
+
+// asks for the solution with hidden letters, used when no -w, -r or --evil is given
+fn read_solution(terminal: &mut DefaultTerminal, final_list: &WordList, acceptable_list: &WordList, word_length: usize)
+-> io::Result<Option<String>>
+{
+    let mut input = String::new();
+    let mut message = String::new();
+    loop
+    {
+        let hidden = "*".repeat(input.chars().count());
+        terminal.draw(|frame|
+        {
+            let lines = vec![
+                Line::from(Span::styled("Enter the solution:", Style::default().fg(Color::Blue))),
+                Line::from(""),
+                Line::from(hidden.clone()),
+                Line::from(""),
+                Line::from(Span::styled(message.clone(), Style::default().fg(Color::Red))),
+            ];
+            let popup = centered(frame.area(), 30, 7);
+            frame.render_widget(Paragraph::new(lines).alignment(Alignment::Center)
+                .block(Block::default().borders(Borders::ALL)), popup);
+        })?;
+
+        match next_key()?
+        {
+            None => return Ok(None),
+            Some(KeyCode::Enter) =>
+            {
+                let word = input.to_uppercase();
+                if wordle::engine::valid_input(&word, true, final_list, acceptable_list, word_length)
+                {
+                    return Ok(Some(word));
+                }
+                message = "Not a valid solution".to_string();
+                input.clear();
+            }
+            Some(KeyCode::Backspace) => { input.pop(); }
+            Some(KeyCode::Char(c)) if c.is_ascii_alphabetic() && input.chars().count() < word_length =>
+            {
+                input.push(c.to_ascii_uppercase());
+                message.clear();
+            }
+            _ => {}
+        }
+    }
+}
+
+// plays one round, returns false if the player quit in the middle of it
+// every accepted guess is saved to the state file until the round is over
+fn play(terminal: &mut DefaultTerminal, game: &mut Game, word_count: &mut HashMap<String, i32>, settings: &game::Settings,
+    next: &game::Round) -> io::Result<bool>
+{
+    let mut input = String::new();
+    let mut message = String::new();
+    let revealed = game.word_length();
+
+    while !game.is_over()
+    {
+        let alphabet = game.alphabet();
+        terminal.draw(|frame| draw(frame, &View { game, input: &input, revealed, message: &message, alphabet }))?;
+
+        match next_key()?
+        {
+            None => return Ok(false),
+            Some(KeyCode::Enter) =>
+            {
+                if input.chars().count() < game.word_length()
+                {
+                    message = "Not enough letters".to_string();
+                    continue;
+                }
+                match game.guess(&input)
+                {
+                    Ok(feedback) =>
+                    {
+                        // STATS MODE START
+                        *word_count.entry(feedback.word.clone()).or_insert(0) += 1;
+                        // STATS MODE END
+                        input.clear();
+                        message.clear();
+                        if let Err(e) = next.save(settings, vec![game.answer().to_string()], game.guesses())
+                        {
+                            message = e;
+                        }
+
+                        // turns the tiles over one at a time, the keyboard keeps
+                        // its old colours until every tile is shown
+                        for i in 0..game.word_length()
+                        {
+                            terminal.draw(|frame| draw(frame, &View { game, input: &input, revealed: i, message: &message, alphabet }))?;
+                            thread::sleep(REVEAL_DELAY);
+                        }
+                    }
+                    Err(e) => message = e.to_string(),
+                }
+            }
+            Some(KeyCode::Backspace) => { input.pop(); message.clear(); }
+            Some(KeyCode::Char(c)) if c.is_ascii_alphabetic() && input.chars().count() < game.word_length() =>
+            {
+                input.push(c.to_ascii_uppercase());
+            }
+            _ => {}
+        }
+    }
+    return Ok(true);
+}
+
+// runs rounds in the terminal ui until the player quits
+pub fn start(settings: &game::Settings) -> io::Result<()>
+{
+    let mut results: Vec<(Outcome, i32)> = Vec::new();
+    let mut word_count: HashMap<String, i32> = HashMap::new();
+    let mut total_rounds: i32 = 0;
+
+    // the full-screen ui has one board, rounds with several are left for the normal output
+    let resume = game::load_history(settings, &mut results, &mut word_count, &mut total_rounds)
+        .filter(|x| x.answers.len() == 1);
+
+    // the terminal is restored even if a round fails
+    let mut terminal = ratatui::init();
+    let result = rounds(&mut terminal, settings, resume, &mut results, &mut word_count, &mut total_rounds);
+    ratatui::restore();
+    return result;
+}
+
+fn rounds(terminal: &mut DefaultTerminal, settings: &game::Settings, mut resume: Option<progress::Unfinished>,
+     results: &mut Vec<(Outcome, i32)>, word_count: &mut HashMap<String, i32>, total_rounds: &mut i32) -> io::Result<()>
+{
+    if let Some(x) = resume.take()
+    {
+        match ask_resume(terminal, x.guesses.len())?
+        {
+            None => return Ok(()),
+            Some(true) => resume = Some(x),
+            // the terminal is cleared when the ui ends, so an error ends the session to be shown
+            Some(false) => match game::give_up(settings, x, results, word_count, total_rounds)
+            {
+                Ok(false) => {}
+                Ok(true) => return Ok(()),
+                Err(e) => return Err(io::Error::new(io::ErrorKind::Other, e)),
+            },
+        }
+    }
+
+    // the day of the next random round
+    let mut day = settings.day;
+    loop
+    {
+        let next = match game::next_round(settings, resume.take(), &mut day)
+        {
+            Some(x) => x,
+            None => return Ok(()),
+        };
+        let mut game = if next.mode.evil
+        {
+            Game::new_evil(settings.final_list, settings.final_list, settings.acceptable_list, next.mode.difficult)
+        }
+        else if let Some(x) = next.answers.first()
+        {
+            Game::new(x, settings.final_list, settings.acceptable_list, next.mode.difficult)
+        }
+        else
+        {
+            match read_solution(terminal, settings.final_list, settings.acceptable_list, settings.word_length)?
+            {
+                Some(x) => Game::new(&x, settings.final_list, settings.acceptable_list, next.mode.difficult),
+                None => return Ok(()),
+            }
+        };
+
+        for word in &next.replay
+        {
+            if game.guess(word).is_ok()
+            {
+                *word_count.entry(word.clone()).or_insert(0) += 1;
+            }
+        }
+
+        if !play(terminal, &mut game, word_count, settings, &next)?
+        {
+            return Ok(());
+        }
+
+        let count = game.history().len() as i32;
+        if let Some(outcome) = game.outcome()
+        {
+            results.push((outcome, count));
+        }
+        *total_rounds += 1;
+
+        // WRITE TO JSON PROGRESS FILE
+        // a failed save is shown below the board, the session goes on
+        let outcome = game.outcome().unwrap_or(Outcome::FAILED);
+        let message = match next.finish(settings, &game.guesses(), vec![game.answer().to_string()], outcome, total_rounds)
+        {
+            Ok(()) => String::new(),
+            Err(e) => e,
+        };
+        // END WRITE TO JSON PROGRESS FILE
+
+        let view = View { game: &game, input: "", revealed: game.word_length(), message: &message, alphabet: game.alphabet() };
+        terminal.draw(|frame| draw_stats(frame, &view, results, word_count, settings.date.is_some()))?;
+        // there is only one daily puzzle per date
+        if settings.date.is_some()
+        {
+            next_key()?;
+            return Ok(());
+        }
+        loop
+        {
+            match next_key()?
+            {
+                Some(KeyCode::Char('y')) | Some(KeyCode::Char('Y')) => break,
+                Some(KeyCode::Char('n')) | Some(KeyCode::Char('N')) | None => return Ok(()),
+                _ => {}
+            }
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use std::collections::HashSet;
+use std::ops::Deref;
+use crate::shuffle::Shuffler;
+
+// a list of words in a fixed order, indexed once so that looking a word up
+// takes constant time instead of a scan over the whole list
+// lookups are made with uppercase words, the words themselves keep their case
+pub struct WordList<'a>
+{
+    words: Vec<&'a str>,
+    uppercase: Vec<String>, // uppercase form of words[i]
+    index: HashSet<String>,
+}
+
+impl<'a> WordList<'a>
+{
+    pub fn new(words: &[&'a str]) -> WordList<'a>
+    {
+        let uppercase: Vec<String> = words.iter().map(|w| w.to_uppercase()).collect();
+        let index: HashSet<String> = uppercase.iter().cloned().collect();
+        WordList { words: words.to_vec(), uppercase, index }
+    }
+
+    // checks if an uppercase word is in the list
+    pub fn contains(&self, word: &str) -> bool
+    {
+        return self.index.contains(word);
+    }
+
+    // every word together with its uppercase form, in list order
+    pub fn pairs(&self) -> impl Iterator<Item = (&'a str, &String)>
+    {
+        return self.words.iter().copied().zip(self.uppercase.iter());
+    }
+
+    // shuffles the words in place, the result is the same as shuffling a
+    // plain vector of the words with the same generator
+    pub fn shuffle(&mut self, rng: &mut Shuffler)
+    {
+        rng.shuffle(&mut self.words);
+        self.uppercase = self.words.iter().map(|w| w.to_uppercase()).collect();
+    }
+}
+
+// the words can be read like a slice: indexed by day, iterated or copied
+impl<'a> Deref for WordList<'a>
+{
+    type Target = [&'a str];
+
+    fn deref(&self) -> &[&'a str]
+    {
+        return &self.words;
+    }
+}
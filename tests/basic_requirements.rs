@@ -65,8 +65,20 @@ fn test_07_5_pts_save_game_state() {
     TestCase::read("07_01_save_state").run_and_compare_game_state();
     // load game state, check statistics and JSON output after several rounds
     TestCase::read("07_02_load_state").run_and_compare_game_state();
-    // load game state from an invalid JSON
-    TestCase::read("07_03_invalid_json_format").run_and_expect_exit();
+    // load game state from an invalid JSON: the file is backed up and new statistics are started
+    TestCase::read("07_03_invalid_json_format").run_and_compare_game_state();
+    let backups: Vec<std::path::PathBuf> = std::fs::read_dir("tests/cases")
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            let name = path.file_name().unwrap().to_string_lossy().to_string();
+            name.starts_with("07_03_invalid_json_format.run.json.") && name.ends_with(".bak")
+        })
+        .collect();
+    assert!(!backups.is_empty(), "case 07_03_invalid_json_format should back up the state file");
+    for path in backups {
+        std::fs::remove_file(path).unwrap();
+    }
 }
 
 #[test]
@@ -18,6 +18,15 @@ lazy_static! {
     static ref MBT_DIR: PathBuf = "./wordle-mbt".into();
 }
 
+// runs the program with a home directory that does not exist, so that no config
+// file of the machine running the tests is read
+#[allow(dead_code)] // only the extended tests use it
+pub fn wordle_isolated() -> Command {
+    let mut command = Command::new(EXE_PATH.as_os_str());
+    command.env("HOME", "tests/data/no_home").env_remove("XDG_CONFIG_HOME");
+    command
+}
+
 pub struct TestCase {
     name: String,
     arguments: Vec<String>,
@@ -145,8 +154,15 @@ impl TestCase {
         self.run_and_compare_result();
 
         // load state and compare with answer
-        let run_state: serde_json::Value =
+        let mut run_state: serde_json::Value =
             serde_json::from_reader(BufReader::new(File::open(&run_state_file).unwrap())).unwrap();
+        // timestamps differ between runs, they are only checked for being there
+        strip_timestamps(&mut run_state, &self.name);
+        if let Some(players) = run_state.get_mut("players").and_then(|p| p.as_object_mut()) {
+            for profile in players.values_mut() {
+                strip_timestamps(profile, &self.name);
+            }
+        }
         let answer_state: serde_json::Value =
             serde_json::from_reader(BufReader::new(File::open(&after_state_file).unwrap()))
                 .unwrap();
@@ -168,3 +184,21 @@ impl TestCase {
         );
     }
 }
+
+// removes the timestamps of the games and the unfinished round of one history
+fn strip_timestamps(profile: &mut serde_json::Value, name: &str) {
+    if let Some(games) = profile.get_mut("games").and_then(|g| g.as_array_mut()) {
+        for game in games {
+            if let Some(game) = game.as_object_mut() {
+                if game.contains_key("finished") {
+                    assert!(game.remove("started").unwrap().is_string(), "case {} started", name);
+                    assert!(game.remove("finished").unwrap().is_string(), "case {} finished", name);
+                    assert!(game.remove("duration_secs").unwrap().is_i64(), "case {} duration", name);
+                }
+            }
+        }
+    }
+    if let Some(unfinished) = profile.get_mut("unfinished").and_then(|u| u.as_object_mut()) {
+        assert!(unfinished.remove("started").unwrap().is_string(), "case {} started", name);
+    }
+}
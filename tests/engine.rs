@@ -0,0 +1,141 @@
+use wordle::builtin_words;
+use wordle::engine::{Game, GuessError, Outcome, Status};
+use wordle::shuffle::{Scheme, Shuffler};
+use wordle::WordList;
+
+#[test]
+fn test_engine_guess_feedback() {
+    let final_list = WordList::new(builtin_words::FINAL);
+    let acceptable_list = WordList::new(builtin_words::ACCEPTABLE);
+    let mut game = Game::new("crane", &final_list, &acceptable_list, false);
+    let feedback = game.guess("react").unwrap();
+    assert_eq!(feedback.word, "REACT");
+    assert_eq!(
+        feedback.statuses,
+        vec![Status::Y, Status::Y, Status::G, Status::Y, Status::R]
+    );
+    assert_eq!(game.alphabet()[0], Status::G);
+    assert_eq!(game.alphabet()[19], Status::R);
+    assert!(!game.is_over());
+    assert_eq!(game.outcome(), None);
+}
+
+#[test]
+fn test_engine_invalid_guess_is_not_counted() {
+    let final_list = WordList::new(builtin_words::FINAL);
+    let acceptable_list = WordList::new(builtin_words::ACCEPTABLE);
+    let mut game = Game::new("crane", &final_list, &acceptable_list, false);
+    assert_eq!(game.guess("abcde"), Err(GuessError::NOT_IN_LIST));
+    assert_eq!(game.guess("cran"), Err(GuessError::WRONG_LENGTH(5)));
+    assert_eq!(game.guess("cr4ne"), Err(GuessError::NOT_ALPHABETIC));
+    assert!(game.history().is_empty());
+}
+
+#[test]
+fn test_engine_difficult_mode() {
+    let final_list = WordList::new(builtin_words::FINAL);
+    let acceptable_list = WordList::new(builtin_words::ACCEPTABLE);
+    let mut game = Game::new("crane", &final_list, &acceptable_list, true);
+    game.guess("react").unwrap();
+    // the green A must stay in place and the yellow R, E and C must be used
+    assert_eq!(game.guess("hello"), Err(GuessError::MISSING_GREEN(2, 'A')));
+    assert_eq!(GuessError::MISSING_GREEN(2, 'A').to_string(), "3rd letter must be A");
+    assert_eq!(game.guess("chant"), Err(GuessError::MISSING_YELLOW('E')));
+    assert_eq!(GuessError::MISSING_YELLOW('E').to_string(), "Guess must contain E");
+    assert!(game.guess("crane").is_ok());
+    assert_eq!(game.outcome(), Some(Outcome::CORRECT));
+}
+
+#[test]
+fn test_engine_game_over() {
+    let final_list = WordList::new(builtin_words::FINAL);
+    let acceptable_list = WordList::new(builtin_words::ACCEPTABLE);
+    let mut game = Game::new("crane", &final_list, &acceptable_list, false);
+    for _ in 0..6 {
+        game.guess("hello").unwrap();
+    }
+    assert!(game.is_over());
+    assert_eq!(game.outcome(), Some(Outcome::FAILED));
+    assert_eq!(game.guess("crane"), Err(GuessError::FINISHED));
+    assert_eq!(game.guesses().len(), 6);
+}
+
+#[test]
+fn test_shuffle_schemes_are_stable() {
+    // these orders must never change, shared puzzles depend on them
+    let mut list: Vec<u32> = (0..10).collect();
+    Shuffler::new(Scheme::CHACHA12, 42).shuffle(&mut list);
+    assert_eq!(list, vec![7, 3, 9, 5, 0, 8, 6, 4, 2, 1]);
+
+    let mut list: Vec<u32> = (0..10).collect();
+    Shuffler::new(Scheme::SPLITMIX64, 42).shuffle(&mut list);
+    assert_eq!(list, vec![8, 3, 6, 5, 4, 0, 9, 2, 1, 7]);
+
+    // day 42 of seed 20220909, as recorded in the seed test cases
+    let mut words = builtin_words::FINAL.to_vec();
+    Shuffler::new(Scheme::CHACHA12, 20220909).shuffle(&mut words);
+    assert_eq!(words[41], "slave");
+    assert_eq!(Scheme::from_version(2), Some(Scheme::SPLITMIX64));
+    assert_eq!(Scheme::from_version(0), None);
+}
+
+#[test]
+fn test_word_list_lookup() {
+    let mut list = WordList::new(&["crane", "Pilot", "react"]);
+    assert!(list.contains("CRANE"));
+    assert!(list.contains("PILOT"));
+    // lookups are made with uppercase words only
+    assert!(!list.contains("crane"));
+    assert!(!list.contains("HELLO"));
+    assert_eq!(list.len(), 3);
+    assert_eq!(list[1], "Pilot");
+
+    // shuffling keeps every word next to its uppercase form, in the order of a plain shuffle
+    let mut words = vec!["crane", "Pilot", "react"];
+    Shuffler::new(Scheme::CHACHA12, 7).shuffle(&mut words);
+    list.shuffle(&mut Shuffler::new(Scheme::CHACHA12, 7));
+    assert_eq!(list.to_vec(), words);
+    for (word, upper) in list.pairs() {
+        assert_eq!(word.to_uppercase(), *upper);
+    }
+
+    // guesses are checked against the indexed built-in lists
+    use wordle::engine::check_input;
+    let final_list = WordList::new(builtin_words::FINAL);
+    let acceptable_list = WordList::new(builtin_words::ACCEPTABLE);
+    let check = |word: &str, is_final: bool| check_input(&word.to_string(), is_final, &final_list, &acceptable_list, 5);
+    assert_eq!(check("PILOT", true), Ok(()));
+    assert_eq!(check("AAHED", false), Ok(()));
+    assert_eq!(check("AAHED", true), Err(GuessError::NOT_IN_LIST));
+    assert_eq!(check("ZZZZZ", false), Err(GuessError::NOT_IN_LIST));
+}
+
+#[test]
+fn test_feedback_codes() {
+    use wordle::engine::compare;
+    use wordle::feedback::{code, codes, decode, encode, CODES};
+
+    // the packed code gives the same statuses as compare, repeated letters included
+    for (answer, guess) in [("CRANE", "REACT"), ("ABBEY", "BABES"), ("SPEED", "EERIE"), ("CRANE", "CRANE")] {
+        let (statuses, _) = compare(&answer.to_string(), guess.to_string());
+        let packed = code(answer, guess);
+        assert_eq!(decode(packed).to_vec(), statuses);
+        assert_eq!(encode(&statuses), packed);
+    }
+    assert_eq!(code("crane", "react"), code("CRANE", "REACT"));
+    assert_eq!(code("CRANE", "CRANE") as usize, CODES - 1);
+    assert_eq!(decode(0), [Status::R; 5]);
+
+    // a shorter word leaves the remaining positions red
+    assert_eq!(decode(code("ABCD", "ABDC"))[..4], [Status::G, Status::G, Status::Y, Status::Y]);
+
+    let answers = ["crane", "pilot", "eerie"];
+    assert_eq!(codes("SPEED", &answers), answers.iter().map(|a| code(a, "SPEED")).collect::<Vec<_>>());
+}
+
+#[test]
+#[should_panic(expected = "at most 5 letters")]
+fn test_feedback_code_refuses_long_words() {
+    // a release build must not read past the packed digits either
+    wordle::feedback::code("STRAWS", "CRANES");
+}
@@ -0,0 +1,389 @@
+use ntest::timeout;
+
+mod common;
+use common::{wordle_isolated, TestCase};
+
+#[test]
+#[timeout(2000)]
+fn test_07_tui() {
+    // hints are not shown in the full-screen ui, so asking for both is refused
+    TestCase::read("07_01_tui_hint_conflict").run_and_expect_exit();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_09_word_length() {
+    // six-letter game with custom word lists
+    TestCase::read("09_01_word_length").run_and_compare_result();
+    // no built-in words of the chosen length
+    TestCase::read("09_02_word_length_without_list").run_and_expect_exit();
+}
+
+#[test]
+#[timeout(3000)]
+fn test_10_hint() {
+    // ranked suggestions over a small word list
+    TestCase::read("10_01_hint").run_and_compare_result();
+}
+
+#[test]
+#[timeout(3000)]
+fn test_11_bench() {
+    // solver benchmark over a small word list in difficult mode
+    TestCase::read("11_01_bench").run_and_compare_result();
+    // unknown solver strategy
+    TestCase::read("11_02_bench_unknown_strategy").run_and_expect_exit();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_12_evil_mode() {
+    // the answer is only fixed once a single candidate is left
+    TestCase::read("12_01_evil").run_and_compare_result();
+    // evil mode cannot be combined with random mode
+    TestCase::read("12_02_evil_conflict_args").run_and_expect_exit();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_13_multiple_boards() {
+    // two boards sharing guesses over two rounds
+    TestCase::read("13_01_two_boards").run_and_compare_result();
+    // a single answer cannot be given for several boards
+    TestCase::read("13_02_boards_conflict_args").run_and_expect_exit();
+    // every board of the first round needs a word of the list
+    TestCase::read("13_03_day_out_of_range").run_and_expect_exit();
+    // the game ends once a later round runs past the end of the list
+    TestCase::read("13_04_words_run_out").run_and_compare_result();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_14_stats() {
+    // played, win %, streaks and the guess distribution of a saved history
+    TestCase::read("14_01_stats").run_and_compare_result();
+    // the statistics are read from a state file
+    TestCase::read("14_02_stats_without_state").run_and_expect_exit();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_15_resume_round() {
+    // the saved guesses are replayed before new ones are read
+    TestCase::read("15_01_resume_round").run_and_compare_game_state();
+    // a round cut short by the end of input stays in the state file
+    TestCase::read("15_02_interrupted_round").run_and_compare_game_state();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_16_daily() {
+    // the puzzle is picked by date and the date is saved with the round
+    TestCase::read("16_01_daily").run_and_compare_game_state();
+    // a date that was already played is refused
+    TestCase::read("16_02_daily_already_played").run_and_expect_exit();
+    // giving up an unfinished daily round uses up the puzzle of that date
+    TestCase::read("16_03_daily_given_up").run_and_compare_game_state();
+    // a seed picks another shuffle for the same date
+    TestCase::read("16_04_daily_seed").run_and_compare_game_state();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_17_shuffle_version() {
+    // the second shuffle scheme is pinned in the new state file
+    TestCase::read("17_01_shuffle_version").run_and_compare_game_state();
+    // a state file pinned to another scheme is refused
+    TestCase::read("17_02_shuffle_version_conflict").run_and_expect_exit();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_18_progress_errors() {
+    // a saved round without guesses counts as a loss instead of crashing
+    TestCase::read("18_01_empty_guesses").run_and_compare_result();
+    // a state file written by a newer version is left alone
+    TestCase::read("18_02_newer_schema").run_and_expect_exit();
+    // and the reason is printed as a message, not as the name of the error
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_wordle"))
+        .args(["stats", "--state", "tests/data/18_02_newer_schema.json"])
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("state file has schema version 99, only 3 and older are supported"), "{}", stderr);
+    assert!(!stderr.contains("VERSION"), "{}", stderr);
+}
+
+#[test]
+#[timeout(5000)]
+fn test_19_concurrent_sessions() {
+    // sessions sharing one state file keep every round
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let state = std::path::Path::new("tests").join("cases").join("19_01_concurrent_sessions.run.json");
+    let _ = std::fs::remove_file(&state);
+    let sessions: Vec<std::process::Child> = (0..8)
+        .map(|_| {
+            let mut child = Command::new(env!("CARGO_BIN_EXE_wordle"))
+                .args(["-w", "pilot", "--state", state.to_str().unwrap()])
+                .stdin(Stdio::piped())
+                .stdout(Stdio::null())
+                .spawn()
+                .unwrap();
+            child.stdin.take().unwrap().write_all(b"crane\npilot\nN\n").unwrap();
+            child
+        })
+        .collect();
+    for mut child in sessions {
+        assert!(child.wait().unwrap().success());
+    }
+
+    let data: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&state).unwrap()).unwrap();
+    assert_eq!(data["total_rounds"], 8);
+    assert_eq!(data["games"].as_array().unwrap().len(), 8);
+}
+
+#[test]
+#[timeout(10000)]
+fn test_20_sqlite_state() {
+    // a history imported into a database keeps growing there and can be exported again
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let cases = std::path::Path::new("tests").join("cases");
+    let database = cases.join("20_01_sqlite_state.run.db");
+    let exported = cases.join("20_01_sqlite_state.run.json");
+    let _ = std::fs::remove_file(&database);
+    let _ = std::fs::remove_file(&exported);
+    let state = format!("sqlite:{}", database.to_str().unwrap());
+    let wordle = |args: &[&str], input: &[u8]| {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_wordle"))
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child.stdin.take().unwrap().write_all(input).unwrap();
+        child
+    };
+
+    let import = wordle(&["--state", &state, "import", "tests/data/14_01_stats.json"], b"");
+    assert!(import.wait_with_output().unwrap().status.success());
+    for _ in 0..4 {
+        let round = wordle(&["-w", "pilot", "--state", &state], b"crane\npilot\nN\n");
+        assert!(round.wait_with_output().unwrap().status.success());
+    }
+
+    let stats = wordle(&["--state", &state, "stats"], b"").wait_with_output().unwrap();
+    assert_eq!(String::from_utf8(stats.stdout).unwrap(), "10 8 2 80.00\n4 4\n0 5 2 1 0 0\n");
+
+    let export = wordle(&["--state", &state, "export", exported.to_str().unwrap()], b"");
+    assert!(export.wait_with_output().unwrap().status.success());
+    let data: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&exported).unwrap()).unwrap();
+    assert_eq!(data["total_rounds"], 10);
+    assert_eq!(data["games"].as_array().unwrap().len(), 10);
+    assert_eq!(data["games"][9]["guesses"], serde_json::json!(["CRANE", "PILOT"]));
+
+    // an export never overwrites another history
+    let again = wordle(&["--state", &state, "export", exported.to_str().unwrap()], b"").wait_with_output().unwrap();
+    assert!(!again.status.success());
+    let stderr = String::from_utf8(again.stderr).unwrap();
+    assert!(stderr.contains(&format!("{} already has saved games, nothing was copied", exported.to_str().unwrap())), "{}", stderr);
+}
+
+#[test]
+#[timeout(2000)]
+fn test_21_players() {
+    // a named player gets a history of their own next to the existing one
+    TestCase::read("21_01_player_round").run_and_compare_game_state();
+    // players ranked by win %, average guesses and max streak
+    TestCase::read("21_02_leaderboard").run_and_compare_result();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_22_config_layers() {
+    // defaults < global config < project config < environment < command line
+    use std::process::Command;
+
+    let show = |args: &[&str]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_wordle"))
+            .args(["-c", "tests/data/22_project.json"])
+            .args(args)
+            .args(["config", "show"])
+            .env("HOME", "tests/data/22_home")
+            .env_remove("XDG_CONFIG_HOME")
+            .env("WORDLE_SEED", "9")
+            .env("WORDLE_EVIL", "off")
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+    let settings = show(&["--length", "6"]);
+    for line in ["difficult true global", "state global.json global", "word - default", "evil false env", "seed 9 env", "length 6 cli"] {
+        assert!(settings.lines().any(|x| x == line), "missing {:?} in\n{}", line, settings);
+    }
+    // a flag turned on in a config file can be turned off again
+    assert!(show(&["--no-difficult"]).lines().any(|x| x == "difficult false cli"));
+}
+
+#[test]
+#[timeout(2000)]
+fn test_23_config_formats() {
+    // a TOML config found in $XDG_CONFIG_HOME and a YAML config given with -c
+    use std::process::Command;
+
+    let xdg = std::env::current_dir().unwrap().join("tests").join("data").join("23_xdg");
+    let output = Command::new(env!("CARGO_BIN_EXE_wordle"))
+        .args(["-c", "tests/data/23_project.yaml", "config", "show"])
+        .env("XDG_CONFIG_HOME", xdg)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let settings = String::from_utf8(output.stdout).unwrap();
+    for line in ["difficult true global", "hint_count 3 global", "state global.json global", "hint true project", "day 4 project", "word - default"] {
+        assert!(settings.lines().any(|x| x == line), "missing {:?} in\n{}", line, settings);
+    }
+}
+
+#[test]
+#[timeout(2000)]
+fn test_24_strict_config() {
+    // unknown keys, wrong types and missing paths fail the check
+    let wordle = |args: &[&str]| wordle_isolated().args(args).output().unwrap();
+    let check = wordle(&["-c", "tests/data/24_invalid_config.json", "config", "check"]);
+    assert!(!check.status.success());
+    let report = String::from_utf8(check.stdout).unwrap();
+    assert_eq!(
+        report,
+        "Unknown key in tests/data/24_invalid_config.json: dificult\n\
+         Failed to parse tests/data/24_invalid_config.json: invalid type: string \"19700101\", expected u64 at line 4 column 20\n"
+    );
+    let check = wordle(&["-c", "tests/data/24_invalid_config.json", "--seed", "1", "-f", "tests/data/24_missing.txt", "config", "check"]);
+    assert!(String::from_utf8(check.stdout).unwrap().contains("final_set tests/data/24_missing.txt does not exist, set in the command line"));
+
+    // a valid config passes, without --strict-config a broken one is skipped
+    let check = wordle(&["-c", "tests/data/24_valid_config.toml", "config", "check"]);
+    assert!(check.status.success());
+    assert_eq!(String::from_utf8(check.stdout).unwrap(), "The settings are valid\n");
+    assert!(wordle(&["-c", "tests/data/24_invalid_config.json", "config", "show"]).status.success());
+    assert!(!wordle(&["-c", "tests/data/24_invalid_config.json", "--strict-config", "config", "show"]).status.success());
+}
+
+#[test]
+#[timeout(20000)]
+fn test_25_word_lookup() {
+    // a bot sending thousands of guesses gets an answer to every one of them
+    // the lookup itself is checked in tests/engine.rs, the timeout only catches a hang
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let mut input = String::new();
+    for _ in 0..20000 {
+        input.push_str("zzzzz\n");
+    }
+    input.push_str("crane\npilot\n");
+    let mut child = wordle_isolated()
+        .args(["-w", "pilot"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdin = child.stdin.take().unwrap();
+    std::thread::spawn(move || stdin.write_all(input.as_bytes()).unwrap());
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    let output = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(output.lines().filter(|l| *l == "INVALID").count(), 20000);
+    assert!(output.ends_with("CORRECT 2\n"));
+}
+
+#[test]
+#[timeout(10000)]
+fn test_26_pattern_cache() {
+    // the precomputed matrix gives the same suggestions and is only used for its own lists
+    use std::io::Write;
+    use std::process::{Output, Stdio};
+
+    let cache = std::env::current_dir().unwrap().join("tests").join("cases").join("26_01_pattern_cache.run");
+    let _ = std::fs::remove_dir_all(&cache);
+    let wordle_with = |acceptable: &str, args: &[&str], input: &[u8]| -> Output {
+        let mut child = wordle_isolated()
+            .args(args)
+            .args(["-f", "tests/data/10_01_hint_final.txt", "-a", acceptable])
+            .env("XDG_CACHE_HOME", &cache)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child.stdin.take().unwrap().write_all(input).unwrap();
+        child.wait_with_output().unwrap()
+    };
+    let wordle = |args: &[&str], input: &[u8]| wordle_with("tests/data/10_01_hint_acceptable.txt", args, input);
+    let hint_input = std::fs::read("tests/cases/10_01_hint.in").unwrap();
+    let hint = ["-w", "grate", "--hint", "--hint-count", "3"];
+    let bench = ["bench", "--strategy", "minimax", "-D"];
+    let computed_hint = wordle(&hint, &hint_input);
+    let computed_bench = wordle(&bench, b"");
+
+    let precompute = wordle(&["precompute"], b"");
+    assert!(precompute.status.success());
+    let files: Vec<_> = std::fs::read_dir(cache.join("wordle")).unwrap().map(|e| e.unwrap().path()).collect();
+    assert_eq!(files.len(), 1);
+    assert!(files[0].file_name().unwrap().to_str().unwrap().starts_with("patterns-"));
+
+    let cached_hint = wordle(&hint, &hint_input);
+    assert_eq!(cached_hint.stdout, computed_hint.stdout);
+    assert!(cached_hint.stderr.is_empty());
+    assert_eq!(wordle(&bench, b"").stdout, computed_bench.stdout);
+
+    // a damaged cache is reported and skipped
+    std::fs::write(&files[0], b"WORDLEPM").unwrap();
+    let damaged = wordle(&bench, b"");
+    assert_eq!(damaged.stdout, computed_bench.stdout);
+    assert!(String::from_utf8(damaged.stderr).unwrap().starts_with("Ignoring the pattern cache"));
+
+    // other lists get a cache file of their own
+    assert!(wordle_with("tests/data/10_01_hint_final.txt", &["precompute"], b"").status.success());
+    assert_eq!(std::fs::read_dir(cache.join("wordle")).unwrap().count(), 2);
+}
+
+#[test]
+#[timeout(10000)]
+fn test_27_parallel_jobs() {
+    // any number of threads gives the output of a single one
+    let wordle = |args: &[&str]| {
+        let output = wordle_isolated()
+            .args(args)
+            .args(["-f", "tests/data/10_01_hint_final.txt", "-a", "tests/data/10_01_hint_acceptable.txt"])
+            .env("XDG_CACHE_HOME", "/nonexistent")
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+    let expected = std::fs::read_to_string("tests/cases/11_01_bench.ans").unwrap();
+    for jobs in ["1", "2", "8"] {
+        assert_eq!(wordle(&["bench", "--strategy", "entropy", "-D", "--jobs", jobs]), expected);
+        assert_eq!(
+            wordle(&["bench", "--strategy", "minimax", "--jobs", jobs]),
+            wordle(&["bench", "--strategy", "minimax", "--jobs", "1"])
+        );
+    }
+    assert!(wordle(&["-j", "3", "config", "show"]).contains("jobs 3 cli"));
+}
+
+#[test]
+#[timeout(3000)]
+fn test_28_openers() {
+    // opening words ranked by expected candidates left
+    TestCase::read("28_01_openers").run_and_compare_result();
+    // whole games played in difficult mode, only for the best words by entropy
+    TestCase::read("28_02_openers_length").run_and_compare_result();
+    // unknown metric
+    TestCase::read("28_03_openers_unknown_metric").run_and_expect_exit();
+}